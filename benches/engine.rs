@@ -0,0 +1,108 @@
+// Throughput of the solver, the rule checker and the generator
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use queens::model::board::Board;
+use queens::model::game_rule_broken::{check_rules, check_rules_on};
+use queens::model::grid::Grid;
+use queens::model::layout::Layout;
+use queens::model::solver::Solver;
+use std::hint::black_box;
+
+const SIZES: [usize; 3] = [6, 8, 10];
+
+// Fixed layouts, so runs can be compared
+fn layouts(n: usize) -> Vec<Layout> {
+    (0..8)
+        .map(|seed| Layout::generate_unique_layout(n, seed).unwrap())
+        .collect()
+}
+
+// A layout with its solution placed on the grid
+fn solved_grid(layout: &Layout) -> Grid {
+    let n = layout.get_n();
+    let mut grid = Grid::from_layout(layout);
+    let solution = &Solver::new(layout, n).unwrap().solutions(Some(1))[0];
+
+    for &c in solution {
+        grid.place_queen(grid.get_coord(c).unwrap()).unwrap();
+    }
+
+    grid
+}
+
+fn solve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("count_solutions");
+
+    for n in SIZES {
+        let layouts = layouts(n);
+        group.bench_with_input(BenchmarkId::from_parameter(n), &layouts, |b, layouts| {
+            b.iter(|| {
+                for layout in layouts {
+                    black_box(Solver::new(layout, n).unwrap().count_solutions(2));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check_rules");
+
+    for n in SIZES {
+        let games: Vec<(Layout, Grid)> = layouts(n)
+            .into_iter()
+            .map(|layout| {
+                let grid = solved_grid(&layout);
+                (layout, grid)
+            })
+            .collect();
+        group.bench_with_input(BenchmarkId::from_parameter(n), &games, |b, games| {
+            b.iter(|| {
+                for (layout, grid) in games {
+                    black_box(check_rules(layout, grid).unwrap());
+                }
+            })
+        });
+    }
+
+    group.finish();
+
+    // As in a game, where the board is built once
+    let mut group = c.benchmark_group("check_rules_on");
+
+    for n in SIZES {
+        let games: Vec<(Board, Layout, Grid)> = layouts(n)
+            .into_iter()
+            .map(|layout| {
+                let grid = solved_grid(&layout);
+                (Board::new(&layout).unwrap(), layout, grid)
+            })
+            .collect();
+        group.bench_with_input(BenchmarkId::from_parameter(n), &games, |b, games| {
+            b.iter(|| {
+                for (board, layout, grid) in games {
+                    black_box(check_rules_on(board, layout, grid).unwrap());
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_unique_layout");
+    group.sample_size(10);
+
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(n), &n, |b, &n| {
+            b.iter(|| black_box(Layout::generate_unique_layout(n, 7).unwrap()))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, solve, check, generate);
+criterion_main!(benches);
@@ -0,0 +1,302 @@
+// Command-line interface to make and check puzzles without a window
+use queens::errors::QueensError;
+use queens::model::difficulty::{Difficulty, Rating};
+use queens::model::game_rule_broken::check_rules;
+use queens::model::grid::Grid;
+use queens::model::layout::Layout;
+use queens::model::pack::Pack;
+use queens::model::solver::{Solution, Solver};
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: queens-cli <command> [options]
+
+Commands:
+  generate -n <size> [--seed <number>] [--difficulty <easy|medium|hard|expert>]
+           [--unique] [--format <text|json>]
+                     Print a new layout
+  solve [file] [--limit <number>] [--format <text|json>]
+                     Print the solutions of a layout
+  validate [file]    Check a layout, and the queens on it if the file has a state block
+  rate [file]        Print how hard a layout with one solution is
+  pack <path>        Check a puzzle pack, or every pack in a directory, and list its levels
+
+Layouts are read as text or JSON from the file, or from standard input without one.";
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+#[derive(Default)]
+struct Args {
+    file: Option<String>,
+    n: Option<usize>,
+    seed: Option<u64>,
+    difficulty: Option<Difficulty>,
+    unique: bool,
+    limit: Option<usize>,
+    json: bool,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> CliResult<Args> {
+        let mut res = Args::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            let mut value = || iter.next().ok_or(format!("{} needs a value", arg));
+
+            match arg.as_str() {
+                "-n" | "--size" => res.n = Some(value()?.parse()?),
+                "--seed" => res.seed = Some(value()?.parse()?),
+                "--difficulty" => {
+                    let name = value()?;
+                    res.difficulty = Some(
+                        Difficulty::ALL
+                            .into_iter()
+                            .find(|d| d.to_string().eq_ignore_ascii_case(name))
+                            .ok_or(format!("Unknown difficulty {}", name))?,
+                    );
+                }
+                "--unique" => res.unique = true,
+                "--limit" => res.limit = Some(value()?.parse()?),
+                "--format" => {
+                    res.json = match value()?.as_str() {
+                        "text" => false,
+                        "json" => true,
+                        format => return Err(format!("Unknown format {}", format).into()),
+                    }
+                }
+                _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg).into()),
+                _ if res.file.is_none() => res.file = Some(arg.clone()),
+                _ => return Err(format!("Unexpected argument {}", arg).into()),
+            }
+        }
+
+        Ok(res)
+    }
+
+    // The input file, or standard input without one
+    fn read_input(&self) -> CliResult<String> {
+        match &self.file {
+            Some(file) => Ok(fs::read_to_string(file)?),
+            None => {
+                let mut text = String::new();
+                std::io::stdin().read_to_string(&mut text)?;
+                Ok(text)
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let res = match args.split_first() {
+        Some((command, rest)) => match command.as_str() {
+            "generate" => Args::parse(rest).and_then(|a| generate(&a)),
+            "solve" => Args::parse(rest).and_then(|a| solve(&a)),
+            "validate" => Args::parse(rest).and_then(|a| validate(&a)),
+            "rate" => Args::parse(rest).and_then(|a| rate(&a)),
+            "pack" => Args::parse(rest).and_then(|a| pack(&a)),
+            "help" | "-h" | "--help" => {
+                println!("{}", USAGE);
+                Ok(ExitCode::SUCCESS)
+            }
+            _ => Err(format!("Unknown command {}\n\n{}", command, USAGE).into()),
+        },
+        None => Err(USAGE.into()),
+    };
+
+    match res {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn is_json(text: &str) -> bool {
+    text.trim_start().starts_with('{')
+}
+
+// A layout as JSON, or else in the text format
+fn parse_layout(text: &str) -> CliResult<Layout> {
+    if !is_json(text) {
+        return Ok(text.parse()?);
+    }
+
+    let layout: Layout = serde_json::from_str(text)?;
+    let problems = layout.validate();
+    if !problems.is_empty() {
+        return Err(QueensError::InvalidLayout { problems }.into());
+    }
+
+    Ok(layout)
+}
+
+// The layout with the queens of a solution on it
+fn solution_grid(layout: &Layout, solution: &Solution) -> CliResult<Grid> {
+    let mut grid = Grid::from_layout(layout);
+
+    for &c in solution {
+        grid.place_queen(grid.get_coord(c)?)?;
+    }
+
+    Ok(grid)
+}
+
+fn generate(args: &Args) -> CliResult<ExitCode> {
+    let n = args.n.ok_or("generate needs a size, like -n 8")?;
+    let seed = args.seed.unwrap_or_else(rand::random);
+
+    let layout = match args.difficulty {
+        Some(difficulty) => {
+            let (layout, rated) = Layout::generate_rated_layout(n, difficulty, seed)?;
+            if rated != difficulty {
+                eprintln!("No {} layout was found, this one is {}", difficulty, rated);
+            }
+            layout
+        }
+        None if args.unique => Layout::generate_unique_layout(n, seed)?,
+        None => Layout::generate_layout(n, seed)?,
+    };
+
+    // Keep the seed out of the layout output, so it can be piped
+    eprintln!("Seed {}", seed);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&layout)?);
+    } else {
+        print!("{}", layout);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn solve(args: &Args) -> CliResult<ExitCode> {
+    let layout = parse_layout(&args.read_input()?)?;
+    let n = layout.get_n();
+    let solutions = Solver::new(&layout, n)?.solutions(args.limit);
+
+    if args.json {
+        let coordinates: Vec<Vec<(usize, usize)>> = solutions
+            .iter()
+            .map(|s| s.iter().map(|c| (c / n, c % n)).collect())
+            .collect();
+        println!("{}", serde_json::to_string(&coordinates)?);
+    } else {
+        for solution in solutions.iter() {
+            println!("{}", solution_grid(&layout, solution)?);
+        }
+    }
+
+    eprintln!("{} solution(s)", solutions.len());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn validate(args: &Args) -> CliResult<ExitCode> {
+    let text = args.read_input()?;
+    let layout = match parse_layout(&text) {
+        Ok(layout) => layout,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let n = layout.get_n();
+
+    let solutions = Solver::new(&layout, n)?.count_solutions(2);
+    match solutions {
+        0 => println!("Layout has no solution"),
+        1 => println!("Layout has a unique solution"),
+        _ => println!("Layout has several solutions"),
+    }
+
+    // Only the text format has queens to check
+    let grid = if is_json(&text) {
+        Grid::from_layout(&layout)
+    } else {
+        text.parse()?
+    };
+    let (broken, won) = check_rules(&layout, &grid)?;
+
+    for rule in broken.iter() {
+        println!("{}", rule);
+    }
+    if won {
+        println!("The queens solve the layout");
+    }
+
+    Ok(if solutions > 0 && broken.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn rate(args: &Args) -> CliResult<ExitCode> {
+    let layout = parse_layout(&args.read_input()?)?;
+    let rating = Rating::rate(&layout, layout.get_n())?;
+
+    println!("Difficulty: {}", rating.difficulty);
+    println!("Score: {}", rating.score);
+    println!("Hardest technique: {}", rating.technique);
+    println!("Branches: {}", rating.branches);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn pack(args: &Args) -> CliResult<ExitCode> {
+    let path = args.file.as_ref().ok_or("pack needs a file or directory")?;
+    let mut code = ExitCode::SUCCESS;
+
+    for pack in Pack::load_path(Path::new(path)) {
+        let pack = match pack {
+            Ok(pack) => pack,
+            Err(e) => {
+                println!("{}", e);
+                code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        println!("{} ({} levels)", pack.name, pack.levels.len());
+        for level in pack.levels.iter() {
+            let n = level.layout.get_n();
+            let solutions = match Solver::new(&level.layout, n) {
+                Ok(solver) => solver.count_solutions(2),
+                // Report the level and go on with the others
+                Err(e) => {
+                    println!("  {}: {}", level.name, e);
+                    code = ExitCode::FAILURE;
+                    continue;
+                }
+            };
+            let difficulty = match level.difficulty {
+                Some(difficulty) => difficulty.to_string(),
+                None => "-".to_string(),
+            };
+
+            println!(
+                "  {}: {} x {}, {}, {}",
+                level.name,
+                n,
+                n,
+                difficulty,
+                match solutions {
+                    0 => "no solution",
+                    1 => "unique solution",
+                    _ => "several solutions",
+                }
+            );
+            if solutions == 0 {
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    Ok(code)
+}
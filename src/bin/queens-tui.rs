@@ -0,0 +1,213 @@
+// Terminal frontend, for playing where no window can be opened
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue};
+use queens::distinct_colors::get_distinct_rgb;
+use queens::model::coord::{Coord, Direction};
+use queens::model::layout::LayoutType;
+use queens::model::state::State;
+use queens::model::tile::TileState;
+use std::error::Error;
+use std::io::{stdout, Write};
+use std::panic;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: queens-tui [-n <size>] [--seed <number>]";
+
+const HELP: [&str; 5] = [
+    "Arrows move the marker, Ctrl+arrows jump to the side",
+    "Space flips a tile, Enter places or removes a queen",
+    "u undoes, r redoes",
+    "n starts a new puzzle",
+    "Escape clears the marker, q quits",
+];
+
+// Width of a tile in characters
+const TILE_WIDTH: usize = 3;
+
+type TuiResult<T> = Result<T, Box<dyn Error>>;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let res = parse_args(&args).and_then(|mut state| {
+        state.load_in_game()?;
+        play(&mut state)
+    });
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> TuiResult<State> {
+    let mut state = State::default();
+    state.set_n(8);
+    state.set_layout_type(LayoutType::GeneratedUnique);
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "-n" | "--size" => state.set_n(value()?.parse()?),
+            "--seed" => state.set_seed(Some(value()?.parse()?)),
+            "-h" | "--help" => return Err(USAGE.into()),
+            _ => return Err(format!("Unexpected argument {}\n{}", arg, USAGE).into()),
+        }
+    }
+
+    Ok(state)
+}
+
+// Run the game in the alternate screen, restoring the terminal however it ends
+fn play(state: &mut State) -> TuiResult<()> {
+    let mut out = stdout();
+
+    // A panic message printed on the alternate screen would be lost, so restore the terminal first
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        hook(info);
+    }));
+
+    terminal::enable_raw_mode()?;
+    let _guard = TerminalGuard;
+    execute!(out, EnterAlternateScreen, cursor::Hide)?;
+
+    run(state, &mut out)
+}
+
+// Restores the terminal when dropped, which also happens when unwinding from a panic
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore_terminal();
+    }
+}
+
+fn restore_terminal() -> std::io::Result<()> {
+    execute!(stdout(), ResetColor, cursor::Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()
+}
+
+fn run(state: &mut State, out: &mut impl Write) -> TuiResult<()> {
+    loop {
+        draw(state, out)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let marked = state.get_marked();
+
+        match key.code {
+            KeyCode::Up => state.move_marker(Direction::Up, ctrl),
+            KeyCode::Down => state.move_marker(Direction::Down, ctrl),
+            KeyCode::Left => state.move_marker(Direction::Left, ctrl),
+            KeyCode::Right => state.move_marker(Direction::Right, ctrl),
+            KeyCode::Char(' ') => {
+                if let Some(c) = marked {
+                    state.flip_tile(c)?;
+                }
+            }
+            KeyCode::Enter => {
+                if let Some(c) = marked {
+                    state.toggle_queen(c)?;
+                }
+            }
+            KeyCode::Char('u') => state.undo()?,
+            KeyCode::Char('r') => state.redo()?,
+            KeyCode::Char('n') => {
+                // A given seed is only for the first puzzle
+                state.set_seed(None);
+                state.load_in_game()?;
+            }
+            KeyCode::Esc if marked.is_some() => state.set_marked(None),
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+            KeyCode::Char('c') if ctrl => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+fn draw(state: &State, out: &mut impl Write) -> TuiResult<()> {
+    let n = state.get_n();
+    let grid = state.get_grid();
+    let marked = state.get_marked();
+    let (broken, won) = state.get_win_status()?;
+
+    queue!(out, terminal::Clear(ClearType::All))?;
+
+    for row in 0..n {
+        queue!(out, cursor::MoveTo(0, row as u16))?;
+
+        for col in 0..n {
+            let coord = Coord { row, col };
+            let tile = grid.get_tile(coord)?;
+            let [r, g, b] = get_distinct_rgb(tile.get_raw_color());
+
+            let symbol = match tile.get_state() {
+                TileState::Empty => ' ',
+                TileState::Crossed | TileState::AutoCrossed => '×',
+                TileState::Queen => '♛',
+            };
+            let text = if marked == Some(coord) {
+                format!("[{}]", symbol)
+            } else {
+                format!(" {} ", symbol)
+            };
+
+            queue!(
+                out,
+                SetBackgroundColor(Color::Rgb { r, g, b }),
+                SetForegroundColor(Color::Black),
+                Print(text)
+            )?;
+        }
+
+        queue!(out, ResetColor)?;
+    }
+
+    // Side panel with the state of the game and the controls
+    let mut lines = Vec::new();
+    if let Some(seed) = state.get_game_seed() {
+        lines.push(format!("Seed {} (n = {})", seed, n));
+        lines.push(String::new());
+    }
+    if let Some((asked, rated)) = state.get_missed_difficulty() {
+        lines.push(format!(
+            "No {} layout was found, this one is {}",
+            asked, rated
+        ));
+        lines.push(String::new());
+    }
+    if won {
+        lines.push("You won!".to_string());
+    } else if broken.is_empty() {
+        lines.push("No rules broken".to_string());
+    } else {
+        lines.push("Rules broken:".to_string());
+        lines.extend(broken.iter().map(|rule| format!("  {}", rule)));
+    }
+    lines.push(String::new());
+    lines.extend(HELP.iter().map(|line| line.to_string()));
+
+    let x = (n * TILE_WIDTH + 2) as u16;
+    for (y, line) in lines.into_iter().enumerate() {
+        queue!(out, cursor::MoveTo(x, y as u16), Print(line))?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
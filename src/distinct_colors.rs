@@ -1,29 +1,28 @@
-use eframe::egui::Color32;
+use crate::model::board::MAX_N;
 
-// todo generalise
-// maybe try https://colorbrewer2.org/#type=qualitative&scheme=Paired&n=12
-// and if that, also just upper bound by 12 (seems reasonable)
-pub fn get_distinct_color(c: u8) -> Color32 {
-    let res = match c {
-        0 => Color32::from_hex("#00008b"),
-        1 => Color32::from_hex("#b03060"),
-        2 => Color32::from_hex("#ff4500"),
-        3 => Color32::from_hex("#ffff00"),
-        4 => Color32::from_hex("#00ff00"),
-        5 => Color32::from_hex("#00ffff"),
-        6 => Color32::from_hex("#ff00ff"),
-        7 => Color32::from_hex("#6495ed"),
-        8 => Color32::from_hex("#ffdead"),
-        9 => Color32::from_hex("#006400"),
-        _ => Ok(Color32::GRAY),
-    };
+// One color per area on the largest grid
+const PALETTE: [[u8; 3]; MAX_N] = [
+    [0x00, 0x00, 0x8b],
+    [0xb0, 0x30, 0x60],
+    [0xff, 0x45, 0x00],
+    [0xff, 0xff, 0x00],
+    [0x00, 0xff, 0x00],
+    [0x00, 0xff, 0xff],
+    [0xff, 0x00, 0xff],
+    [0x64, 0x95, 0xed],
+    [0xff, 0xde, 0xad],
+    [0x00, 0x64, 0x00],
+    [0x8b, 0x45, 0x13],
+    [0x94, 0x00, 0xd3],
+    [0x2f, 0x4f, 0x4f],
+    [0xdd, 0xa0, 0xdd],
+    [0x80, 0x80, 0x00],
+    [0xff, 0x14, 0x93],
+];
 
-    match res {
-        Ok(color) => color,
-        _ => Color32::GRAY,
-    }
-}
+const GRAY: [u8; 3] = [0xa0, 0xa0, 0xa0];
 
-pub fn get_distinct_checked_color(c: u8) -> Color32 {
-    get_distinct_color(c).gamma_multiply_u8(191)
+// Red, green and blue of an area color
+pub fn get_distinct_rgb(c: u8) -> [u8; 3] {
+    PALETTE.get(c as usize).copied().unwrap_or(GRAY)
 }
@@ -1,36 +1,239 @@
+use crate::model::coord::Coord;
+use crate::model::layout::LayoutType;
+use crate::model::layout_problem::LayoutProblem;
 use std::borrow::Cow;
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 
 // Error that cannot be resolved by game logic, and is thus handled by restarting the game.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum QueensError {
-    AreaNotFound { c: usize },
-    OutOfBounds { c: usize },
-    Invalid2DCoordinate { column: usize, row: usize, n: usize },
-    RefreshRequested,
+    AreaNotFound {
+        c: usize,
+    },
+    OutOfBounds {
+        c: usize,
+    },
+    Invalid2DCoordinate {
+        coord: Coord,
+        n: usize,
+    },
     NotIngame,
+    NotInEditor,
+    NoUniqueSolution {
+        solutions: usize,
+    },
+    GridTooSmall {
+        n: usize,
+        min: usize,
+    },
+    GridTooLarge {
+        n: usize,
+        max: usize,
+    },
+    UnsupportedSize {
+        layout_type: LayoutType,
+        n: usize,
+    },
+    EmptyLayout,
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    WrongRowCount {
+        expected: usize,
+        found: usize,
+    },
+    LayoutSizeMismatch {
+        layout: usize,
+        n: usize,
+    },
+    GridSizeMismatch {
+        layout: usize,
+        grid: usize,
+        tiles: usize,
+    },
+    InvalidMove {
+        coord: Coord,
+    },
+    TileColorMismatch {
+        coord: Coord,
+    },
+    InvalidSymbol {
+        line: usize,
+        column: usize,
+        symbol: char,
+    },
+    WrongAreaCount {
+        expected: usize,
+        found: usize,
+    },
+    NonContiguousArea {
+        symbol: char,
+    },
+    TrailingText {
+        line: usize,
+    },
+    InvalidLayout {
+        problems: Vec<LayoutProblem>,
+    },
+    NoSave,
+    SaveFailed {
+        reason: String,
+    },
+    LoadFailed {
+        reason: String,
+    },
+    UnsupportedSaveVersion {
+        found: u32,
+        expected: u32,
+    },
+    InvalidPack {
+        pack: String,
+        reason: String,
+    },
+    WrongSolution {
+        reason: String,
+    },
+    LevelNotFound {
+        pack: String,
+        level: String,
+    },
+    GenerationCancelled,
+    GenerationFailed,
 }
 
 impl Display for QueensError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             QueensError::OutOfBounds { c } => write!(f, "Coordinate {} out of bounds", c),
-            QueensError::Invalid2DCoordinate { column, row, n } => write!(
+            QueensError::Invalid2DCoordinate { coord, n } => write!(
                 f,
-                "2D coordinate ({}, {}) is invalid for {} by {} grid",
-                column, row, n, n
+                "2D coordinate {} is invalid for {} by {} grid",
+                coord, n, n
             ),
             QueensError::AreaNotFound { c } => write!(f, "Area not found at coordinate {}", c),
-            QueensError::RefreshRequested => write!(f, "Refresh request was requested"),
             QueensError::NotIngame => write!(f, "Not ingame"),
+            QueensError::NotInEditor => write!(f, "Not in the editor"),
+            QueensError::NoUniqueSolution { solutions } => {
+                write!(f, "Layout has {} solutions instead of one", solutions)
+            }
+            QueensError::GridTooSmall { n, min } => {
+                write!(f, "Grid size {} is smaller than the minimum of {}", n, min)
+            }
+            QueensError::GridTooLarge { n, max } => {
+                write!(f, "Grid size {} is larger than the maximum of {}", n, max)
+            }
+            QueensError::UnsupportedSize { layout_type, n } => {
+                let sizes = layout_type.get_sizes();
+                if sizes.start() == sizes.end() {
+                    write!(f, "{} needs n = {}, not {}", layout_type, sizes.start(), n)
+                } else {
+                    write!(
+                        f,
+                        "{} needs n from {} to {}, not {}",
+                        layout_type,
+                        sizes.start(),
+                        sizes.end(),
+                        n
+                    )
+                }
+            }
+            QueensError::EmptyLayout => write!(f, "Layout is empty"),
+            QueensError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {} has {} tiles, expected {} like the first row",
+                line, found, expected
+            ),
+            QueensError::WrongRowCount { expected, found } => write!(
+                f,
+                "Found {} rows, expected {} to make a square grid",
+                found, expected
+            ),
+            QueensError::LayoutSizeMismatch { layout, n } => {
+                write!(f, "Layout of size {} does not fit n = {}", layout, n)
+            }
+            QueensError::GridSizeMismatch {
+                layout,
+                grid,
+                tiles,
+            } => write!(
+                f,
+                "Grid of size {} with {} tiles does not fit a layout of size {}",
+                grid, tiles, layout
+            ),
+            QueensError::InvalidMove { coord } => {
+                write!(f, "Saved move on tile {} does not fit the grid", coord)
+            }
+            QueensError::TileColorMismatch { coord } => {
+                write!(
+                    f,
+                    "Saved tile {} does not have the color of its area",
+                    coord
+                )
+            }
+            QueensError::InvalidSymbol {
+                line,
+                column,
+                symbol,
+            } => write!(
+                f,
+                "Invalid symbol '{}' on line {}, column {}",
+                symbol, line, column
+            ),
+            QueensError::WrongAreaCount { expected, found } => write!(
+                f,
+                "Found {} areas, expected one per row ({})",
+                found, expected
+            ),
+            QueensError::NonContiguousArea { symbol } => {
+                write!(f, "Area '{}' is split into separate parts", symbol)
+            }
+            QueensError::TrailingText { line } => {
+                write!(f, "Unexpected text after the state block on line {}", line)
+            }
+            QueensError::InvalidLayout { problems } => {
+                write!(f, "Invalid layout:")?;
+                for problem in problems {
+                    write!(f, "\n{}", problem)?;
+                }
+                Ok(())
+            }
+            QueensError::NoSave => write!(f, "There is no saved game"),
+            QueensError::SaveFailed { reason } => write!(f, "Could not save the file: {}", reason),
+            QueensError::LoadFailed { reason } => {
+                write!(f, "Could not load the file: {}", reason)
+            }
+            QueensError::UnsupportedSaveVersion { found, expected } => write!(
+                f,
+                "Saved game has version {}, only version {} can be loaded",
+                found, expected
+            ),
+            QueensError::InvalidPack { pack, reason } => {
+                write!(f, "Pack {} is invalid: {}", pack, reason)
+            }
+            QueensError::WrongSolution { reason } => {
+                write!(f, "The solution is wrong: {}", reason)
+            }
+            QueensError::LevelNotFound { pack, level } => {
+                write!(f, "There is no level {} in pack {}", level, pack)
+            }
+            QueensError::GenerationCancelled => write!(f, "Layout generation was cancelled"),
+            QueensError::GenerationFailed => write!(f, "Layout generation stopped unexpectedly"),
         }
     }
 }
 
-impl Into<Cow<'static, str>> for QueensError {
-    fn into(self) -> Cow<'static, str> {
-        Cow::from(format!("{}", self))
+impl std::error::Error for QueensError {}
+
+impl From<QueensError> for Cow<'static, str> {
+    fn from(value: QueensError) -> Self {
+        Cow::from(format!("{}", value))
     }
 }
 
@@ -0,0 +1,14 @@
+// Queens puzzle engine: layouts, generation, solving and rule checking.
+// It does no drawing, that is left to the frontends in the binaries.
+
+pub mod distinct_colors;
+pub mod errors;
+pub mod model;
+
+pub use errors::{QueensError, QueensResult};
+pub use model::coord::{Coord, Direction};
+pub use model::game_rule_broken::{check_rules, GameRuleBroken};
+pub use model::grid::Grid;
+pub use model::layout::{Area, Layout, LayoutType};
+pub use model::state::InGameState;
+pub use model::tile::{Tile, TileState};
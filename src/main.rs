@@ -1,15 +1,17 @@
-pub mod distinct_colors;
-pub mod errors;
-mod model;
 mod view;
 
-use crate::model::state::{GameState, State};
+use crate::view::editor::editor_ui::EditorUi;
+use crate::view::format_duration;
+use crate::view::generating::generating_ui::GeneratingUi;
 use crate::view::in_game::in_game_ui::InGameUi;
+use crate::view::levels::levels_ui::LevelsUi;
 use crate::view::main_menu::main_menu_ui::MainMenuUi;
-use eframe::egui::{Align2, CentralPanel, Context, Key, Vec2, Window};
+use crate::view::statistics::statistics_ui::StatisticsUi;
+use eframe::egui::{CentralPanel, Context, Key, Window};
 use eframe::{egui, Frame};
-use errors::QueensResult;
 use log::warn;
+use queens::errors::{QueensError, QueensResult};
+use queens::model::state::{GameState, State, Summary};
 // ONLY the main functionality
 
 fn main() -> eframe::Result {
@@ -29,7 +31,7 @@ fn main() -> eframe::Result {
             // This gives us image support:
             egui_extras::install_image_loaders(&cc.egui_ctx);
 
-            Ok(Box::<QueensApp>::default())
+            Ok(Box::new(QueensApp::resumed()))
         }),
     )
 }
@@ -39,6 +41,12 @@ struct QueensApp {
     state: State,
     in_game_ui: InGameUi,
     main_menu_ui: MainMenuUi,
+    statistics_ui: StatisticsUi,
+    editor_ui: EditorUi,
+    generating_ui: GeneratingUi,
+    levels_ui: LevelsUi,
+    // Error shown to the player after returning to the main menu
+    error: Option<QueensError>,
 }
 
 impl eframe::App for QueensApp {
@@ -46,22 +54,77 @@ impl eframe::App for QueensApp {
         if let Err(e) = match self.state.get_game_state() {
             GameState::MainMenu => self.main_menu_ui.render(ctx, &mut self.state),
             GameState::InGame(_) => self.in_game_ui.render(ctx, &mut self.state),
-            GameState::Won => self.render_won(ctx),
+            GameState::Generating => self.generating_ui.render(ctx, &mut self.state),
+            GameState::Won(summary) => self.render_won(ctx, *summary),
+            GameState::Statistics => self.statistics_ui.render(ctx, &mut self.state),
+            GameState::Editor(_) => self.editor_ui.render(ctx, &mut self.state),
+            GameState::Levels => self.levels_ui.render(ctx, &mut self.state),
         } {
-            warn!("{}", e); // if not debug give error window?
+            warn!("{}", e);
 
             self.state = State::default(); // reset to main menu
 
+            self.error = Some(e.clone());
+
             ctx.request_discard(e); // refresh rendering
         }
+
+        self.render_error(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(e) = self.state.save() {
+            warn!("{}", e);
+        }
     }
 }
 
 impl QueensApp {
-    fn render_won(&mut self, ctx: &Context) -> QueensResult<()> {
+    // Continue the game saved on exit, if there is one
+    fn resumed() -> QueensApp {
+        let mut app = QueensApp::default();
+
+        match app.state.continue_game() {
+            Ok(()) => {}
+            Err(QueensError::NoSave) => {}
+            Err(e) => {
+                warn!("{}", e);
+                app.error = Some(e);
+            }
+        }
+
+        app
+    }
+
+    fn render_error(&mut self, ctx: &Context) {
+        let Some(error) = &self.error else {
+            return;
+        };
+
+        let mut open = true;
+        Window::new("Something went wrong")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(error.to_string());
+                if ui.button("OK").clicked() {
+                    open = false;
+                }
+            });
+
+        if !open {
+            self.error = None;
+        }
+    }
+
+    fn render_won(&mut self, ctx: &Context, summary: Summary) -> QueensResult<()> {
         CentralPanel::default().show(ctx, |ui| {
             ui.vertical(|ui| {
                 ui.label("You won!");
+                ui.label(format!("Time: {}", format_duration(summary.time)));
+                ui.label(format!("Moves: {}", summary.moves));
+                ui.label(format!("Undos: {}", summary.undos));
+                ui.label(format!("Hints: {}", summary.hints));
                 if ui.button("Go to main menu").clicked() {
                     self.state = State::default();
                 }
@@ -0,0 +1,217 @@
+use crate::errors::{QueensError, QueensResult};
+use crate::model::coord::Coord;
+use crate::model::layout::Layout;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+// Smallest grid with a solution, apart from the single tile
+pub const MIN_N: usize = 4;
+
+// Largest grid a bitboard can hold
+pub const MAX_N: usize = 16;
+
+const WORDS: usize = MAX_N * MAX_N / 64;
+
+// Set of tile indices on a grid of up to MAX_N by MAX_N
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard {
+    words: [u64; WORDS],
+}
+
+impl Bitboard {
+    pub fn new() -> Bitboard {
+        Bitboard::default()
+    }
+
+    // The first size tiles
+    pub fn full(size: usize) -> Bitboard {
+        let mut res = Bitboard::new();
+        for (i, word) in res.words.iter_mut().enumerate() {
+            let bits = size.saturating_sub(i * 64).min(64);
+            *word = if bits == 64 {
+                u64::MAX
+            } else {
+                (1 << bits) - 1
+            };
+        }
+        res
+    }
+
+    pub fn from_tiles(tiles: &[usize]) -> Bitboard {
+        let mut res = Bitboard::new();
+        for &c in tiles {
+            res.insert(c);
+        }
+        res
+    }
+
+    pub fn insert(&mut self, c: usize) {
+        self.words[c / 64] |= 1 << (c % 64);
+    }
+
+    pub fn remove(&mut self, c: usize) {
+        self.words[c / 64] &= !(1 << (c % 64));
+    }
+
+    pub fn contains(&self, c: usize) -> bool {
+        c < MAX_N * MAX_N && self.words[c / 64] & (1 << (c % 64)) != 0
+    }
+
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    // Tile indices in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = usize> {
+        let words = self.words;
+        (0..WORDS).flat_map(move |i| {
+            let mut word = words[i];
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+
+    fn bitand(mut self, rhs: Bitboard) -> Bitboard {
+        self &= rhs;
+        self
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Bitboard) {
+        for (word, other) in self.words.iter_mut().zip(rhs.words) {
+            *word &= other;
+        }
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+
+    fn bitor(mut self, rhs: Bitboard) -> Bitboard {
+        self |= rhs;
+        self
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Bitboard) {
+        for (word, other) in self.words.iter_mut().zip(rhs.words) {
+            *word |= other;
+        }
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(mut self) -> Bitboard {
+        for word in self.words.iter_mut() {
+            *word = !*word;
+        }
+        self
+    }
+}
+
+// Rows, columns, areas and neighbourhoods of a layout as bitboards, for fast rule checks
+#[derive(Clone, Debug)]
+pub struct Board {
+    n: usize,
+    // Index into the areas of the layout for each tile
+    area_of: Vec<usize>,
+    rows: Vec<Bitboard>,
+    cols: Vec<Bitboard>,
+    areas: Vec<Bitboard>,
+    // Tiles around each tile, diagonals included
+    neighbours: Vec<Bitboard>,
+}
+
+impl Board {
+    pub fn new(layout: &Layout) -> QueensResult<Board> {
+        let n = layout.get_n();
+        if n > MAX_N {
+            return Err(QueensError::GridTooLarge { n, max: MAX_N });
+        }
+
+        let size = n.pow(2);
+        let mut area_of = vec![usize::MAX; size];
+        let mut areas = Vec::new();
+
+        for (i, area) in layout.get_areas().iter().enumerate() {
+            for &c in area.get_sections() {
+                match area_of.get_mut(c) {
+                    Some(a) => *a = i,
+                    None => return Err(QueensError::OutOfBounds { c }),
+                }
+            }
+            areas.push(Bitboard::from_tiles(area.get_sections()));
+        }
+
+        if let Some(c) = area_of.iter().position(|&a| a == usize::MAX) {
+            return Err(QueensError::AreaNotFound { c });
+        }
+
+        let mut rows = vec![Bitboard::new(); n];
+        let mut cols = vec![Bitboard::new(); n];
+        let mut neighbours = vec![Bitboard::new(); size];
+
+        for (c, around) in neighbours.iter_mut().enumerate() {
+            let coord = Coord {
+                row: c / n,
+                col: c % n,
+            };
+            rows[coord.row].insert(c);
+            cols[coord.col].insert(c);
+            for t in coord.neighbours(n) {
+                around.insert(t.get_index(n));
+            }
+        }
+
+        Ok(Board {
+            n,
+            area_of,
+            rows,
+            cols,
+            areas,
+            neighbours,
+        })
+    }
+
+    pub fn get_n(&self) -> usize {
+        self.n
+    }
+
+    // Index into the areas of the layout
+    pub fn get_area_of(&self, c: usize) -> usize {
+        self.area_of[c]
+    }
+
+    pub fn get_rows(&self) -> &Vec<Bitboard> {
+        &self.rows
+    }
+
+    pub fn get_cols(&self) -> &Vec<Bitboard> {
+        &self.cols
+    }
+
+    pub fn get_areas(&self) -> &Vec<Bitboard> {
+        &self.areas
+    }
+
+    pub fn get_neighbours(&self, c: usize) -> Bitboard {
+        self.neighbours[c]
+    }
+}
@@ -0,0 +1,231 @@
+use crate::errors::{QueensError, QueensResult};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+// Direction to move in on the grid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ];
+}
+
+// What a step over the side of the grid does
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movement {
+    // Continue on the opposite side
+    Wrap,
+    // Stay on the side
+    Clamp,
+}
+
+// Position of a tile, 0-indexed from the top left corner.
+// Tiles are stored row by row, so a coordinate is also an index of row * n + col.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub struct Coord {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Coord {
+    // Checked to be on a grid of size n
+    pub fn new(row: usize, col: usize, n: usize) -> QueensResult<Coord> {
+        if row >= n || col >= n {
+            return Err(QueensError::Invalid2DCoordinate {
+                coord: Coord { row, col },
+                n,
+            });
+        }
+
+        Ok(Coord { row, col })
+    }
+
+    pub fn from_index(c: usize, n: usize) -> QueensResult<Coord> {
+        if c >= n.pow(2) {
+            return Err(QueensError::OutOfBounds { c });
+        }
+
+        Ok(Coord {
+            row: c / n,
+            col: c % n,
+        })
+    }
+
+    pub fn get_index(&self, n: usize) -> usize {
+        self.row * n + self.col
+    }
+
+    // The tile next to this one, none over the side
+    pub fn step(&self, direction: Direction, n: usize) -> Option<Coord> {
+        let (row, col) = match direction {
+            Direction::Up => (self.row.checked_sub(1)?, self.col),
+            Direction::Down => (self.row + 1, self.col),
+            Direction::Left => (self.row, self.col.checked_sub(1)?),
+            Direction::Right => (self.row, self.col + 1),
+        };
+
+        Coord::new(row, col, n).ok()
+    }
+
+    // The tile next to this one, where stepping over the side either wraps or clamps
+    pub fn moved(&self, direction: Direction, movement: Movement, n: usize) -> Coord {
+        match (self.step(direction, n), movement) {
+            (Some(coord), _) => coord,
+            (None, Movement::Clamp) => *self,
+            (None, Movement::Wrap) => match direction {
+                Direction::Up => Coord {
+                    row: n - 1,
+                    ..*self
+                },
+                Direction::Down => Coord { row: 0, ..*self },
+                Direction::Left => Coord {
+                    col: n - 1,
+                    ..*self
+                },
+                Direction::Right => Coord { col: 0, ..*self },
+            },
+        }
+    }
+
+    // The tile on the side of the grid in a direction
+    pub fn to_edge(&self, direction: Direction, n: usize) -> Coord {
+        match direction {
+            Direction::Up => Coord { row: 0, ..*self },
+            Direction::Down => Coord {
+                row: n - 1,
+                ..*self
+            },
+            Direction::Left => Coord { col: 0, ..*self },
+            Direction::Right => Coord {
+                col: n - 1,
+                ..*self
+            },
+        }
+    }
+
+    // Up to four tiles sharing a side with this one
+    pub fn orthogonal_neighbours(&self, n: usize) -> impl Iterator<Item = Coord> {
+        let coord = *self;
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| coord.step(direction, n))
+    }
+
+    // Up to eight tiles around this one, diagonals included
+    pub fn neighbours(&self, n: usize) -> impl Iterator<Item = Coord> {
+        let coord = *self;
+        let rows = self.row.saturating_sub(1)..(self.row + 2).min(n);
+        let cols = self.col.saturating_sub(1)..(self.col + 2).min(n);
+
+        rows.flat_map(move |row| cols.clone().map(move |col| Coord { row, col }))
+            .filter(move |other| *other != coord)
+    }
+
+    // Whether the tiles are next to each other, diagonals included
+    pub fn touches(&self, other: &Coord) -> bool {
+        self != other && self.row.abs_diff(other.row) <= 1 && self.col.abs_diff(other.col) <= 1
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: usize = 4;
+
+    #[test]
+    fn moved_wraps_over_the_sides() {
+        let corner = Coord { row: 0, col: 0 };
+
+        assert_eq!(
+            corner.moved(Direction::Up, Movement::Wrap, N),
+            Coord { row: 3, col: 0 }
+        );
+        assert_eq!(
+            corner.moved(Direction::Left, Movement::Wrap, N),
+            Coord { row: 0, col: 3 }
+        );
+        assert_eq!(
+            Coord { row: 3, col: 3 }.moved(Direction::Down, Movement::Wrap, N),
+            Coord { row: 0, col: 3 }
+        );
+        assert_eq!(
+            Coord { row: 2, col: 3 }.moved(Direction::Right, Movement::Wrap, N),
+            Coord { row: 2, col: 0 }
+        );
+    }
+
+    #[test]
+    fn moved_clamps_on_the_sides() {
+        let corner = Coord { row: 3, col: 0 };
+
+        assert_eq!(corner.moved(Direction::Down, Movement::Clamp, N), corner);
+        assert_eq!(corner.moved(Direction::Left, Movement::Clamp, N), corner);
+        assert_eq!(
+            corner.moved(Direction::Up, Movement::Clamp, N),
+            Coord { row: 2, col: 0 }
+        );
+    }
+
+    #[test]
+    fn step_over_the_side_is_none() {
+        assert_eq!(Coord { row: 0, col: 2 }.step(Direction::Up, N), None);
+        assert_eq!(Coord { row: 3, col: 2 }.step(Direction::Down, N), None);
+        assert_eq!(Coord { row: 2, col: 0 }.step(Direction::Left, N), None);
+        assert_eq!(Coord { row: 2, col: 3 }.step(Direction::Right, N), None);
+        assert_eq!(
+            Coord { row: 2, col: 2 }.step(Direction::Right, N),
+            Some(Coord { row: 2, col: 3 })
+        );
+    }
+
+    #[test]
+    fn to_edge_keeps_the_other_axis() {
+        let coord = Coord { row: 1, col: 2 };
+
+        assert_eq!(coord.to_edge(Direction::Up, N), Coord { row: 0, col: 2 });
+        assert_eq!(coord.to_edge(Direction::Down, N), Coord { row: 3, col: 2 });
+        assert_eq!(coord.to_edge(Direction::Left, N), Coord { row: 1, col: 0 });
+        assert_eq!(coord.to_edge(Direction::Right, N), Coord { row: 1, col: 3 });
+    }
+
+    #[test]
+    fn neighbours_stop_at_the_sides() {
+        let corner = Coord { row: 0, col: 0 };
+        let edge = Coord { row: 0, col: 2 };
+        let middle = Coord { row: 1, col: 1 };
+
+        assert_eq!(corner.neighbours(N).count(), 3);
+        assert_eq!(edge.neighbours(N).count(), 5);
+        assert_eq!(middle.neighbours(N).count(), 8);
+        assert_eq!(corner.orthogonal_neighbours(N).count(), 2);
+        assert_eq!(edge.orthogonal_neighbours(N).count(), 3);
+        assert!(corner.neighbours(N).all(|other| corner.touches(&other)));
+    }
+
+    #[test]
+    fn from_index_checks_the_size() {
+        assert_eq!(Coord::from_index(6, N).unwrap(), Coord { row: 1, col: 2 });
+        assert_eq!(Coord { row: 1, col: 2 }.get_index(N), 6);
+        assert!(Coord::from_index(16, N).is_err());
+        assert!(Coord::new(0, 4, N).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::errors::QueensResult;
+use crate::model::save;
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+const DAILY_FILE: &str = "daily.json";
+
+// Sizes the daily puzzle is picked from
+const DAILY_SIZES: RangeInclusive<usize> = 7..=10;
+
+// Puzzle of a calendar day, the same on every machine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Daily {
+    pub date: NaiveDate,
+    pub seed: u64,
+    pub n: usize,
+}
+
+impl Daily {
+    pub fn today() -> Daily {
+        Self::of(Local::now().date_naive())
+    }
+
+    pub fn of(date: NaiveDate) -> Daily {
+        let seed = mix(date.num_days_from_ce() as u64);
+        let sizes = (DAILY_SIZES.end() - DAILY_SIZES.start() + 1) as u64;
+        let n = DAILY_SIZES.start() + (mix(seed) % sizes) as usize;
+
+        Daily { date, seed, n }
+    }
+}
+
+// Scramble a number, so consecutive days get unrelated seeds (splitmix64)
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// A solved daily puzzle
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Completion {
+    pub date: NaiveDate,
+    pub time: Duration,
+}
+
+// Daily puzzles solved on this machine
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DailyLog {
+    completions: Vec<Completion>,
+}
+
+impl DailyLog {
+    pub fn load() -> QueensResult<DailyLog> {
+        Ok(save::read_json(DAILY_FILE)?.unwrap_or_default())
+    }
+
+    pub fn store(&self) -> QueensResult<()> {
+        save::write_json(DAILY_FILE, self)
+    }
+
+    // Record a solved daily, keeping the first time of each day
+    pub fn record(&mut self, date: NaiveDate, time: Duration) {
+        if self.get(date).is_none() {
+            self.completions.push(Completion { date, time });
+        }
+    }
+
+    pub fn get(&self, date: NaiveDate) -> Option<&Completion> {
+        self.completions.iter().find(|c| c.date == date)
+    }
+
+    pub fn streak(&self, today: NaiveDate) -> usize {
+        let dates: Vec<NaiveDate> = self.completions.iter().map(|c| c.date).collect();
+        streak(&dates, today)
+    }
+}
+
+// Days in a row with a date in dates up to today, or up to yesterday while today has none
+pub fn streak(dates: &[NaiveDate], today: NaiveDate) -> usize {
+    let mut day = if dates.contains(&today) {
+        Some(today)
+    } else {
+        today.pred_opt()
+    };
+    let mut streak = 0;
+
+    while let Some(date) = day.filter(|d| dates.contains(d)) {
+        streak += 1;
+        day = date.pred_opt();
+    }
+
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    #[test]
+    fn a_date_always_gives_the_same_puzzle() {
+        // Pinned, as every machine must agree on the puzzle of a day
+        assert_eq!(
+            Daily::of(day(14)),
+            Daily {
+                date: day(14),
+                seed: 3927179634604795473,
+                n: 10
+            }
+        );
+        assert_ne!(Daily::of(day(14)).seed, Daily::of(day(15)).seed);
+
+        for d in 1..=31 {
+            assert!(DAILY_SIZES.contains(&Daily::of(day(d)).n));
+        }
+    }
+
+    #[test]
+    fn streak_counts_up_to_today() {
+        let dates = [day(8), day(9), day(10)];
+
+        assert_eq!(streak(&dates, day(10)), 3);
+    }
+
+    #[test]
+    fn streak_keeps_yesterday_until_today_is_solved() {
+        let dates = [day(8), day(9)];
+
+        assert_eq!(streak(&dates, day(10)), 2);
+        assert_eq!(streak(&dates, day(11)), 0);
+    }
+
+    #[test]
+    fn streak_stops_at_a_gap() {
+        let dates = [day(5), day(6), day(8), day(9), day(10)];
+
+        assert_eq!(streak(&dates, day(10)), 3);
+        assert_eq!(streak(&[], day(10)), 0);
+    }
+
+    #[test]
+    fn log_keeps_the_first_time_of_a_day() {
+        let mut log = DailyLog::default();
+        log.record(day(9), Duration::from_secs(60));
+        log.record(day(10), Duration::from_secs(90));
+        log.record(day(10), Duration::from_secs(30));
+
+        assert_eq!(log.get(day(10)).unwrap().time, Duration::from_secs(90));
+        assert_eq!(log.streak(day(10)), 2);
+    }
+}
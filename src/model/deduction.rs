@@ -0,0 +1,550 @@
+use crate::errors::{QueensError, QueensResult};
+use crate::model::board::{Bitboard, Board};
+use crate::model::coord::Coord;
+use crate::model::grid::Grid;
+use crate::model::layout::Layout;
+use crate::model::tile::TileState;
+use std::fmt::{Display, Formatter};
+
+// Something that needs exactly one queen
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Row(usize),
+    Column(usize),
+    Area(u8),
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unit::Row(row) => write!(f, "row {}", row),
+            Unit::Column(col) => write!(f, "column {}", col),
+            Unit::Area(color) => write!(f, "area {}", color),
+        }
+    }
+}
+
+// Techniques a human uses on a Queens board, roughly from easiest to hardest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Technique {
+    // A queen rules out its row, column, area and neighbours.
+    PlacedQueen,
+    // A row, column or area with a single tile left must hold the queen there.
+    LastTile,
+    // An area confined to one row or column, or a row or column confined to one area.
+    Confinement,
+    // A queen on a tile would leave no room in some row, column or area.
+    Touching,
+    // N areas confined to N rows or columns, or the other way around.
+    MultiConfinement,
+}
+
+impl Display for Technique {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Technique::PlacedQueen => write!(f, "Placed queen"),
+            Technique::LastTile => write!(f, "Last tile"),
+            Technique::Confinement => write!(f, "Confinement"),
+            Technique::Touching => write!(f, "Touching"),
+            Technique::MultiConfinement => write!(f, "Multi confinement"),
+        }
+    }
+}
+
+// Why a step is forced
+#[derive(Clone, Debug, PartialEq)]
+pub enum Explanation {
+    Queen { coord: Coord },
+    LastTile { unit: Unit, coord: Coord },
+    // Every tile left in `units` lies within `within`, so `within` has no room for other queens.
+    Confined { units: Vec<Unit>, within: Vec<Unit> },
+    Touching { coord: Coord, unit: Unit },
+}
+
+impl Explanation {
+    pub fn technique(&self) -> Technique {
+        match self {
+            Explanation::Queen { .. } => Technique::PlacedQueen,
+            Explanation::LastTile { .. } => Technique::LastTile,
+            Explanation::Confined { units, .. } if units.len() == 1 => Technique::Confinement,
+            Explanation::Confined { .. } => Technique::MultiConfinement,
+            Explanation::Touching { .. } => Technique::Touching,
+        }
+    }
+}
+
+fn join(units: &[Unit]) -> String {
+    units
+        .iter()
+        .map(|u| u.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Explanation::Queen { coord } => write!(
+                f,
+                "The queen at {} rules out its row, column, area and neighbours",
+                coord
+            ),
+            Explanation::LastTile { unit, coord } => write!(
+                f,
+                "{} is the only tile left in {}, so it holds a queen",
+                coord, unit
+            ),
+            Explanation::Confined { units, within } => write!(
+                f,
+                "The tiles left in {} all lie in {}, so no other queen fits there",
+                join(units),
+                join(within)
+            ),
+            Explanation::Touching { coord, unit } => {
+                write!(f, "A queen at {} would leave no tile in {}", coord, unit)
+            }
+        }
+    }
+}
+
+// A single logical deduction
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub explanation: Explanation,
+    // Tiles that must hold a queen
+    pub queens: Vec<Coord>,
+    // Tiles that cannot hold a queen
+    pub eliminated: Vec<Coord>,
+    // Tiles the deduction follows from
+    pub cause: Vec<Coord>,
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.explanation.technique(), self.explanation)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cell {
+    Unknown,
+    Eliminated,
+    Queen,
+}
+
+// Logical solver applying human techniques one step at a time.
+#[derive(Clone, Debug)]
+pub struct Deducer {
+    n: usize,
+    cells: Vec<Cell>,
+    // Rows, then columns, then areas.
+    units: Vec<(Unit, Vec<usize>)>,
+    // The row, column and area of each tile, as indices into units.
+    units_of: Vec<[usize; 3]>,
+    // The tiles a queen on each tile rules out.
+    attacks: Vec<Bitboard>,
+}
+
+impl Deducer {
+    pub fn new(layout: &Layout, n: usize) -> QueensResult<Deducer> {
+        let board = Board::new(layout)?;
+        if board.get_n() != n {
+            return Err(QueensError::LayoutSizeMismatch {
+                layout: board.get_n(),
+                n,
+            });
+        }
+
+        let rows = board.get_rows().iter().enumerate();
+        let cols = board.get_cols().iter().enumerate();
+        let areas = board.get_areas().iter().zip(layout.get_areas());
+        let units: Vec<(Unit, Vec<usize>)> = rows
+            .map(|(row, tiles)| (Unit::Row(row), tiles.iter().collect()))
+            .chain(cols.map(|(col, tiles)| (Unit::Column(col), tiles.iter().collect())))
+            .chain(
+                areas.map(|(tiles, area)| (Unit::Area(area.get_color()), tiles.iter().collect())),
+            )
+            .collect();
+        let units_of = (0..n.pow(2))
+            .map(|c| [c / n, n + c % n, 2 * n + board.get_area_of(c)])
+            .collect();
+        let attacks = (0..n.pow(2))
+            .map(|c| {
+                let mut attacks = board.get_neighbours(c)
+                    | board.get_rows()[c / n]
+                    | board.get_cols()[c % n]
+                    | board.get_areas()[board.get_area_of(c)];
+                attacks.remove(c);
+                attacks
+            })
+            .collect();
+
+        Ok(Self {
+            n,
+            cells: vec![Cell::Unknown; n.pow(2)],
+            units,
+            units_of,
+            attacks,
+        })
+    }
+
+    // Start from the queens and crosses already placed on a grid
+    pub fn from_grid(layout: &Layout, grid: &Grid) -> QueensResult<Deducer> {
+        let mut deducer = Self::new(layout, grid.get_n())?;
+
+        for (c, tile) in grid.get_data().iter().enumerate() {
+            match tile.get_state() {
+                TileState::Queen => deducer.place_queen(c),
+                TileState::Crossed | TileState::AutoCrossed => deducer.cells[c] = Cell::Eliminated,
+                TileState::Empty => {}
+            }
+        }
+
+        Ok(deducer)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cells.iter().filter(|c| **c == Cell::Queen).count() == self.n
+            && !self.cells.contains(&Cell::Unknown)
+    }
+
+    // Whether some row, column or area has no room left for its queen
+    pub fn has_contradiction(&self) -> bool {
+        self.units
+            .iter()
+            .any(|(_, tiles)| tiles.iter().all(|&c| self.cells[c] == Cell::Eliminated))
+    }
+
+    pub fn apply(&mut self, step: &Step) {
+        for c in step.eliminated.iter() {
+            self.cells[c.get_index(self.n)] = Cell::Eliminated;
+        }
+        for c in step.queens.iter() {
+            self.cells[c.get_index(self.n)] = Cell::Queen;
+        }
+    }
+
+    // Place a queen without a deduction, e.g. as a guess
+    pub fn place_queen(&mut self, c: usize) {
+        self.cells[c] = Cell::Queen;
+    }
+
+    // The tiles left in the row, column or area without a queen that has the fewest of them
+    pub fn most_constrained(&self) -> Option<Vec<usize>> {
+        (0..self.units.len())
+            .filter(|&u| !self.has_queen(u))
+            .map(|u| self.unknown(&self.units[u].1))
+            .filter(|tiles| !tiles.is_empty())
+            .min_by_key(|tiles| tiles.len())
+    }
+
+    // Apply steps until solved or no technique makes progress.
+    pub fn solve(&mut self) -> Vec<Step> {
+        let mut steps = Vec::new();
+
+        while let Some(step) = self.next_step() {
+            self.apply(&step);
+            steps.push(step);
+        }
+
+        steps
+    }
+
+    // The easiest deduction available, if any.
+    pub fn next_step(&self) -> Option<Step> {
+        if self.has_contradiction() {
+            return None;
+        }
+
+        self.placed_queen()
+            .or_else(|| self.last_tile())
+            .or_else(|| self.confinement(1))
+            .or_else(|| self.touching())
+            .or_else(|| (2..self.n).find_map(|k| self.confinement(k)))
+    }
+
+    fn coord(&self, c: usize) -> Coord {
+        Coord {
+            row: c / self.n,
+            col: c % self.n,
+        }
+    }
+
+    fn coords(&self, tiles: &[usize]) -> Vec<Coord> {
+        tiles.iter().map(|&c| self.coord(c)).collect()
+    }
+
+    fn unknown(&self, tiles: &[usize]) -> Vec<usize> {
+        tiles
+            .iter()
+            .copied()
+            .filter(|&c| self.cells[c] == Cell::Unknown)
+            .collect()
+    }
+
+    fn has_queen(&self, u: usize) -> bool {
+        self.units[u]
+            .1
+            .iter()
+            .any(|&c| self.cells[c] == Cell::Queen)
+    }
+
+    // Tiles ruled out by a queen on c
+    fn attacked(&self, c: usize) -> Vec<usize> {
+        self.attacks[c].iter().collect()
+    }
+
+    fn placed_queen(&self) -> Option<Step> {
+        let n = self.n;
+
+        (0..n.pow(2))
+            .filter(|&c| self.cells[c] == Cell::Queen)
+            .find_map(|c| {
+                let eliminated = self.unknown(&self.attacked(c));
+
+                (!eliminated.is_empty()).then(|| Step {
+                    explanation: Explanation::Queen {
+                        coord: self.coord(c),
+                    },
+                    queens: Vec::new(),
+                    eliminated: self.coords(&eliminated),
+                    cause: vec![self.coord(c)],
+                })
+            })
+    }
+
+    fn last_tile(&self) -> Option<Step> {
+        self.units
+            .iter()
+            .enumerate()
+            .find_map(|(u, (unit, tiles))| {
+                let left = self.unknown(tiles);
+
+                (left.len() == 1 && !self.has_queen(u)).then(|| Step {
+                    explanation: Explanation::LastTile {
+                        unit: *unit,
+                        coord: self.coord(left[0]),
+                    },
+                    queens: self.coords(&left),
+                    eliminated: Vec::new(),
+                    cause: self.coords(tiles),
+                })
+            })
+    }
+
+    fn touching(&self) -> Option<Step> {
+        let n = self.n;
+
+        (0..n.pow(2))
+            .filter(|&c| self.cells[c] == Cell::Unknown)
+            .find_map(|c| {
+                let attacked = self.attacked(c);
+
+                self.units
+                    .iter()
+                    .enumerate()
+                    .filter(|(u, _)| !self.units_of[c].contains(u) && !self.has_queen(*u))
+                    .find(|(_, (_, tiles))| {
+                        self.unknown(tiles).iter().all(|t| attacked.contains(t))
+                    })
+                    .map(|(_, (unit, tiles))| Step {
+                        explanation: Explanation::Touching {
+                            coord: self.coord(c),
+                            unit: *unit,
+                        },
+                        queens: Vec::new(),
+                        eliminated: vec![self.coord(c)],
+                        cause: self.coords(&self.unknown(tiles)),
+                    })
+            })
+    }
+
+    // Look for k open units of one kind whose tiles left lie within k units of another kind.
+    fn confinement(&self, k: usize) -> Option<Step> {
+        let n = self.n;
+        let rows = 0..n;
+        let cols = n..2 * n;
+        let areas = 2 * n..self.units.len();
+
+        // (kind confined, kind confined to, index of the kind confined to in units_of)
+        let pairs = [(areas.clone(), 0), (areas.clone(), 1), (rows, 2), (cols, 2)];
+
+        pairs
+            .into_iter()
+            .find_map(|(confined, to)| self.confinement_between(confined, to, k))
+    }
+
+    fn confinement_between(
+        &self,
+        confined: std::ops::Range<usize>,
+        to: usize,
+        k: usize,
+    ) -> Option<Step> {
+        // For each open unit, the units of the other kind its tiles left lie in
+        let open: Vec<(usize, Vec<usize>)> = confined
+            .filter(|&u| !self.has_queen(u))
+            .map(|u| {
+                let mut within: Vec<usize> = self
+                    .unknown(&self.units[u].1)
+                    .iter()
+                    .map(|&c| self.units_of[c][to])
+                    .collect();
+                within.sort();
+                within.dedup();
+                (u, within)
+            })
+            .filter(|(_, within)| within.len() <= k)
+            .collect();
+
+        self.find_confined(&open, 0, k, &mut Vec::new(), &[])
+    }
+
+    // Try every combination of k open units, skipping those spread over more than k units.
+    fn find_confined(
+        &self,
+        open: &[(usize, Vec<usize>)],
+        start: usize,
+        k: usize,
+        chosen: &mut Vec<usize>,
+        within: &[usize],
+    ) -> Option<Step> {
+        if chosen.len() == k {
+            let units: Vec<usize> = chosen.iter().map(|&i| open[i].0).collect();
+            let eliminated: Vec<usize> = within
+                .iter()
+                .flat_map(|&w| self.unknown(&self.units[w].1))
+                .filter(|c| !units.iter().any(|u| self.units_of[*c].contains(u)))
+                .collect();
+
+            return (!eliminated.is_empty()).then(|| Step {
+                explanation: Explanation::Confined {
+                    units: units.iter().map(|&u| self.units[u].0).collect(),
+                    within: within.iter().map(|&w| self.units[w].0).collect(),
+                },
+                queens: Vec::new(),
+                eliminated: self.coords(&eliminated),
+                cause: units
+                    .iter()
+                    .flat_map(|&u| self.unknown(&self.units[u].1))
+                    .map(|c| self.coord(c))
+                    .collect(),
+            });
+        }
+
+        for i in start..open.len() {
+            let mut union = within.to_vec();
+            union.extend(open[i].1.iter().copied());
+            union.sort();
+            union.dedup();
+
+            if union.len() > k {
+                continue;
+            }
+
+            chosen.push(i);
+            let res = self.find_confined(open, i + 1, k, chosen, &union);
+            chosen.pop();
+
+            if res.is_some() {
+                return res;
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::difficulty::Difficulty;
+    use crate::model::solver::Solver;
+
+    fn coords(tiles: &[(usize, usize)]) -> Vec<Coord> {
+        tiles.iter().map(|&(row, col)| Coord { row, col }).collect()
+    }
+
+    fn deducer(layout: &str) -> Deducer {
+        let layout: Layout = layout.parse().unwrap();
+        Deducer::new(&layout, layout.get_n()).unwrap()
+    }
+
+    #[test]
+    fn placed_queen() {
+        let mut deducer = deducer("AAAA\nBBBB\nCCCC\nDDDD");
+        deducer.place_queen(0);
+
+        let step = deducer.next_step().unwrap();
+        assert_eq!(step.explanation.technique(), Technique::PlacedQueen);
+        assert_eq!(
+            step.eliminated,
+            coords(&[(0, 1), (0, 2), (0, 3), (1, 0), (1, 1), (2, 0), (3, 0)])
+        );
+    }
+
+    #[test]
+    fn last_tile() {
+        let deducer = deducer("ABBB\nBBBB\nCCCC\nDDDD");
+
+        let step = deducer.next_step().unwrap();
+        assert_eq!(step.explanation.technique(), Technique::LastTile);
+        assert_eq!(step.queens, coords(&[(0, 0)]));
+    }
+
+    #[test]
+    fn confinement() {
+        // Area A only has tiles in the first row
+        let deducer = deducer("AABB\nBBBB\nCCCC\nDDDD");
+
+        let step = deducer.next_step().unwrap();
+        assert_eq!(step.explanation.technique(), Technique::Confinement);
+        assert_eq!(step.eliminated, coords(&[(0, 2), (0, 3)]));
+    }
+
+    #[test]
+    fn touching() {
+        // Only (1, 0) and (1, 1) are left in the second row, which a queen at (0, 0) touches
+        let mut deducer = deducer("AAAA\nBBBB\nCCCC\nDDDD");
+        deducer.cells[6] = Cell::Eliminated;
+        deducer.cells[7] = Cell::Eliminated;
+
+        let step = deducer.touching().unwrap();
+        assert_eq!(step.explanation.technique(), Technique::Touching);
+        assert_eq!(step.eliminated, coords(&[(0, 0)]));
+    }
+
+    #[test]
+    fn multi_confinement() {
+        // Areas A and B fill the first two rows, apart from the last column
+        let deducer = deducer("AABBC\nAABBC\nCCCCC\nDDDDD\nEEEEE");
+
+        let step = deducer.confinement(2).unwrap();
+        assert_eq!(step.explanation.technique(), Technique::MultiConfinement);
+        assert_eq!(step.eliminated, coords(&[(0, 4), (1, 4)]));
+    }
+
+    #[test]
+    fn solves_unique_layouts() {
+        for seed in 0..5 {
+            let (layout, _) = Layout::generate_rated_layout(8, Difficulty::Easy, seed).unwrap();
+            let mut deducer = Deducer::new(&layout, 8).unwrap();
+            deducer.solve();
+
+            let queens: Vec<usize> = (0..64)
+                .filter(|&c| deducer.cells[c] == Cell::Queen)
+                .collect();
+            let solutions = Solver::new(&layout, 8).unwrap().solutions(None);
+            assert!(deducer.is_solved());
+            assert_eq!(solutions, vec![queens]);
+        }
+    }
+
+    #[test]
+    fn wrong_size() {
+        let layout = Layout::easy_layout(5).unwrap();
+        assert!(matches!(
+            Deducer::new(&layout, 6),
+            Err(QueensError::LayoutSizeMismatch { layout: 5, n: 6 })
+        ));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::errors::{QueensError, QueensResult};
+use crate::model::deduction::{Deducer, Technique};
+use crate::model::layout::Layout;
+use crate::model::solver::Solver;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Hard => write!(f, "Hard"),
+            Difficulty::Expert => write!(f, "Expert"),
+        }
+    }
+}
+
+// Points added to the score for each guess the deductions cannot avoid
+const BRANCH_WEIGHT: u32 = 10;
+
+fn technique_weight(technique: Technique) -> u32 {
+    match technique {
+        Technique::PlacedQueen => 0,
+        Technique::LastTile => 1,
+        Technique::Confinement => 2,
+        Technique::Touching => 3,
+        Technique::MultiConfinement => 5,
+    }
+}
+
+// How hard a layout is for a human to solve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    pub score: u32,
+    // Hardest technique needed
+    pub technique: Technique,
+    // Guesses needed where the techniques get stuck, weighted by how many options there were
+    pub branches: u32,
+}
+
+impl Rating {
+    // Rate a layout by solving it the way a human would.
+    // Only layouts with exactly one solution can be rated.
+    pub fn rate(layout: &Layout, n: usize) -> QueensResult<Rating> {
+        let mut solutions = Solver::new(layout, n)?.solutions(Some(2));
+        if solutions.len() != 1 {
+            return Err(QueensError::NoUniqueSolution {
+                solutions: solutions.len(),
+            });
+        }
+        let solution = solutions.remove(0);
+
+        let mut deducer = Deducer::new(layout, n)?;
+        let mut score = 0;
+        let mut technique = Technique::PlacedQueen;
+        let mut branches = 0;
+
+        loop {
+            for step in deducer.solve() {
+                let t = step.explanation.technique();
+                score += technique_weight(t);
+                technique = technique.max(t);
+            }
+
+            if deducer.is_solved() {
+                break;
+            }
+
+            // Guess in the smallest open row, column or area, counting the wrong options
+            match deducer.most_constrained() {
+                Some(tiles) => {
+                    branches += tiles.len() as u32 - 1;
+                    match tiles.into_iter().find(|c| solution.contains(c)) {
+                        Some(c) => deducer.place_queen(c),
+                        None => break,
+                    }
+                }
+                None => break,
+            }
+        }
+
+        score += branches * BRANCH_WEIGHT;
+
+        let difficulty = if branches > 0 {
+            Difficulty::Expert
+        } else {
+            match technique {
+                Technique::PlacedQueen | Technique::LastTile | Technique::Confinement => {
+                    Difficulty::Easy
+                }
+                Technique::Touching => Difficulty::Medium,
+                Technique::MultiConfinement => Difficulty::Hard,
+            }
+        };
+
+        Ok(Rating {
+            difficulty,
+            score,
+            technique,
+            branches,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(layout: &str) -> QueensResult<Rating> {
+        let layout: Layout = layout.parse()?;
+        Rating::rate(&layout, layout.get_n())
+    }
+
+    #[test]
+    fn rates_known_layouts() {
+        let easy = "BBBBB\nBBBBE\nCBBBA\nCCAAA\nDDAAA";
+        let medium = "CBBBBB\nCCEBBD\nCEEEED\nAAEEDD\nAAEEDD\nAAAAAF";
+        let hard = "GGGGFFFB\nGGDFFFBB\nGDDDDFAA\nGCDDDFAA\nCCCAAAAA\nCCCCHAEA\nCCCCEEEE\nCCCCEEEE";
+        let expert =
+            "HHAAAAABB\nHAAAAAAFB\nAACAAAAFB\nAACCAAFFF\nDAACCCEEF\nDAAGCEEEF\nDDDGCEEEE\nDDDGGGGEI\nDDGGGGGEE";
+
+        assert_eq!(rate(easy).unwrap().difficulty, Difficulty::Easy);
+        assert_eq!(rate(medium).unwrap().difficulty, Difficulty::Medium);
+        assert_eq!(rate(hard).unwrap().difficulty, Difficulty::Hard);
+        assert_eq!(rate(expert).unwrap().difficulty, Difficulty::Expert);
+    }
+
+    #[test]
+    fn rejects_layouts_without_a_unique_solution() {
+        let layout = Layout::complex_layout(10).unwrap();
+        assert!(matches!(
+            Rating::rate(&layout, 10),
+            Err(QueensError::NoUniqueSolution { solutions: 2 })
+        ));
+    }
+}
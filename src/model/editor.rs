@@ -0,0 +1,270 @@
+use crate::errors::{QueensError, QueensResult};
+use crate::model::board::{MAX_N, MIN_N};
+use crate::model::coord::Coord;
+use crate::model::layout::{section, Area, Layout};
+use crate::model::layout_problem::LayoutProblem;
+use crate::model::save;
+use crate::model::solver::Solver;
+use serde::{Deserialize, Serialize};
+
+// Solutions to count before calling a layout ambiguous
+pub const SOLUTION_LIMIT: usize = 100;
+
+// Folder in the data directory the editor saves to
+const LAYOUT_DIR: &str = "layouts";
+
+// Layout painted by hand, one area color per tile
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Editor {
+    n: usize,
+    colors: Vec<u8>,
+    // Color painted onto tiles
+    brush: u8,
+    // Solutions counted since the last change, up to SOLUTION_LIMIT
+    #[serde(skip)]
+    solutions: Option<usize>,
+}
+
+impl Editor {
+    // Starts with one area per row, which is already a valid layout
+    pub fn new(n: usize) -> Editor {
+        let n = n.clamp(MIN_N, MAX_N);
+
+        Editor {
+            n,
+            colors: (0..n.pow(2)).map(|c| (c / n) as u8).collect(),
+            brush: 0,
+            solutions: None,
+        }
+    }
+
+    pub fn get_n(&self) -> usize {
+        self.n
+    }
+
+    // Resize the grid, keeping the top left corner. New tiles are colored by their row.
+    pub fn set_n(&mut self, n: usize) {
+        let n = n.clamp(MIN_N, MAX_N);
+        let old = self.n;
+        let max_color = (n - 1) as u8;
+
+        self.colors = (0..n.pow(2))
+            .map(|c| {
+                let (row, col) = (c / n, c % n);
+                let color = if row < old && col < old {
+                    self.colors[row * old + col]
+                } else {
+                    row as u8
+                };
+                color.min(max_color)
+            })
+            .collect();
+        self.n = n;
+        self.brush = self.brush.min(max_color);
+        self.solutions = None;
+    }
+
+    pub fn get_color(&self, coord: Coord) -> QueensResult<u8> {
+        Ok(self.colors[self.get_index(coord)?])
+    }
+
+    // Color a tile with the brush
+    pub fn paint(&mut self, coord: Coord) -> QueensResult<()> {
+        let c = self.get_index(coord)?;
+        if self.colors[c] != self.brush {
+            self.colors[c] = self.brush;
+            self.solutions = None;
+        }
+        Ok(())
+    }
+
+    fn get_index(&self, coord: Coord) -> QueensResult<usize> {
+        Ok(Coord::new(coord.row, coord.col, self.n)?.get_index(self.n))
+    }
+
+    pub fn get_brush(&self) -> u8 {
+        self.brush
+    }
+
+    pub fn set_brush(&mut self, brush: u8) {
+        self.brush = brush.min((self.n - 1) as u8);
+    }
+
+    // One area per brush color, unused colors giving empty areas
+    pub fn get_layout(&self) -> Layout {
+        let areas = (0..self.n as u8)
+            .map(|color| {
+                let sections = (0..self.colors.len())
+                    .filter(|&c| self.colors[c] == color)
+                    .map(section)
+                    .collect();
+                Area::from_sections(sections, color)
+            })
+            .collect();
+
+        Layout::new(areas, self.n)
+    }
+
+    pub fn validate(&self) -> Vec<LayoutProblem> {
+        self.get_layout().validate()
+    }
+
+    // Count the solutions of a valid layout, kept until the next change
+    pub fn solve(&mut self) -> QueensResult<usize> {
+        let layout = self.get_layout();
+        let problems = layout.validate();
+        if !problems.is_empty() {
+            return Err(QueensError::InvalidLayout { problems });
+        }
+
+        let count = Solver::new(&layout, self.n)?.count_solutions(SOLUTION_LIMIT);
+        self.solutions = Some(count);
+        Ok(count)
+    }
+
+    pub fn get_solutions(&self) -> Option<usize> {
+        self.solutions
+    }
+
+    // Save a valid layout as text or JSON under the given name in the layouts folder.
+    // Gives the path of the file.
+    pub fn store(&self, name: &str, json: bool) -> QueensResult<String> {
+        let name = name.trim();
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(QueensError::SaveFailed {
+                reason: format!("{:?} is not a valid file name", name),
+            });
+        }
+
+        let layout = self.get_layout();
+        let problems = layout.validate();
+        if !problems.is_empty() {
+            return Err(QueensError::InvalidLayout { problems });
+        }
+
+        let file = if json {
+            let file = format!("{}/{}.json", LAYOUT_DIR, name);
+            save::write_json(&file, &layout)?;
+            file
+        } else {
+            let file = format!("{}/{}.txt", LAYOUT_DIR, name);
+            save::write_text(&file, &layout.to_string())?;
+            file
+        };
+
+        Ok(save::get_path(&file)?.display().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Editor with every tile painted like the letters of the text format
+    fn editor(rows: &str) -> Editor {
+        let rows: Vec<&str> = rows.lines().collect();
+        let mut editor = Editor::new(rows.len());
+        for (row, line) in rows.iter().enumerate() {
+            for (col, letter) in line.bytes().enumerate() {
+                editor.set_brush(letter - b'A');
+                editor.paint(Coord { row, col }).unwrap();
+            }
+        }
+        editor
+    }
+
+    #[test]
+    fn starts_with_a_valid_layout() {
+        let editor = Editor::new(6);
+
+        assert_eq!(editor.get_n(), 6);
+        assert!(editor.validate().is_empty());
+        assert_eq!(Editor::new(MAX_N + 1).get_n(), MAX_N);
+    }
+
+    #[test]
+    fn resizing_keeps_the_top_left_corner() {
+        let mut editor = Editor::new(5);
+        editor.set_brush(4);
+        editor.paint(Coord { row: 0, col: 0 }).unwrap();
+        editor.paint(Coord { row: 3, col: 3 }).unwrap();
+
+        editor.set_n(6);
+        assert_eq!(editor.get_color(Coord { row: 0, col: 0 }).unwrap(), 4);
+        assert_eq!(editor.get_color(Coord { row: 3, col: 3 }).unwrap(), 4);
+        assert_eq!(editor.get_color(Coord { row: 5, col: 5 }).unwrap(), 5);
+        assert_eq!(editor.get_color(Coord { row: 0, col: 5 }).unwrap(), 0);
+
+        // Colors past the smaller size are brought down to the last one
+        editor.set_n(4);
+        assert_eq!(editor.get_color(Coord { row: 0, col: 0 }).unwrap(), 3);
+        assert_eq!(editor.get_brush(), 3);
+        assert!(editor.get_color(Coord { row: 4, col: 0 }).is_err());
+    }
+
+    #[test]
+    fn paints_with_the_brush() {
+        let mut editor = Editor::new(4);
+        editor.set_brush(2);
+        editor.paint(Coord { row: 1, col: 1 }).unwrap();
+
+        assert_eq!(editor.get_color(Coord { row: 1, col: 1 }).unwrap(), 2);
+        assert!(matches!(
+            editor.paint(Coord { row: 4, col: 0 }),
+            Err(QueensError::Invalid2DCoordinate { .. })
+        ));
+
+        editor.set_brush(9);
+        assert_eq!(editor.get_brush(), 3);
+    }
+
+    #[test]
+    fn validates_while_painting() {
+        let mut editor = Editor::new(4);
+
+        // Moving a tile to the area next to it keeps the layout valid
+        editor.set_brush(0);
+        editor.paint(Coord { row: 1, col: 0 }).unwrap();
+        assert!(editor.validate().is_empty());
+
+        // An area of 0 in the middle of row 2 is split from the rest
+        editor.paint(Coord { row: 2, col: 2 }).unwrap();
+        assert!(editor
+            .validate()
+            .contains(&LayoutProblem::NonContiguous { color: 0 }));
+
+        // Painting every tile of a row over leaves its area empty
+        for row in 1..4 {
+            for col in 0..4 {
+                editor.paint(Coord { row, col }).unwrap();
+            }
+        }
+        assert!(editor
+            .validate()
+            .contains(&LayoutProblem::EmptyArea { color: 1 }));
+        assert!(matches!(
+            editor.solve(),
+            Err(QueensError::InvalidLayout { .. })
+        ));
+    }
+
+    #[test]
+    fn counts_solutions_until_the_next_change() {
+        let mut editor = editor("BBBBB\nBBBBE\nCBBBA\nCCAAA\nDDAAA");
+        assert_eq!(editor.get_solutions(), None);
+        assert_eq!(editor.solve().unwrap(), 1);
+        assert_eq!(editor.get_solutions(), Some(1));
+
+        // Painting a tile its own color changes nothing
+        editor.set_brush(1);
+        editor.paint(Coord { row: 0, col: 0 }).unwrap();
+        assert_eq!(editor.get_solutions(), Some(1));
+
+        editor.set_brush(4);
+        editor.paint(Coord { row: 0, col: 4 }).unwrap();
+        assert_eq!(editor.get_solutions(), None);
+
+        editor.set_n(6);
+        assert_eq!(editor.get_solutions(), None);
+    }
+}
@@ -0,0 +1,324 @@
+// Plain-text puzzle format.
+//
+// A layout is n lines of n symbols, where each letter or digit is an area:
+//
+//     AABBB
+//     ACCCB
+//     ADDCB
+//     ADEEB
+//     AAEEE
+//
+// A grid adds a second block after a blank line with the state of each tile:
+// '.' for empty, 'x' for crossed out and 'Q' for a queen.
+// Whitespace within a line is ignored.
+
+use crate::errors::{QueensError, QueensResult};
+use crate::model::coord::Coord;
+use crate::model::grid::Grid;
+use crate::model::layout::{connected, section, Area, Layout};
+use crate::model::tile::{Tile, TileState};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Symbols used when printing, indexed by area color
+const SYMBOLS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+const EMPTY: char = '.';
+const CROSSED: char = 'x';
+const QUEEN: char = 'Q';
+
+// Symbol of an area color in the text format
+pub fn symbol(color: u8) -> char {
+    SYMBOLS.chars().nth(color as usize).unwrap_or('?')
+}
+
+// Rows of a block with their 1-indexed line numbers
+type Block = Vec<(usize, Vec<char>)>;
+
+// Split text into its layout block and optional state block
+fn blocks(s: &str) -> QueensResult<(Block, Option<Block>)> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut in_block = false;
+
+    for (i, line) in s.lines().enumerate() {
+        let symbols: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+
+        if symbols.is_empty() {
+            in_block = false;
+            continue;
+        }
+
+        if !in_block {
+            if blocks.len() == 2 {
+                return Err(QueensError::TrailingText { line: i + 1 });
+            }
+            blocks.push(Vec::new());
+            in_block = true;
+        }
+
+        if let Some(block) = blocks.last_mut() {
+            block.push((i + 1, symbols));
+        }
+    }
+
+    let mut blocks = blocks.into_iter();
+    match blocks.next() {
+        Some(layout) => Ok((layout, blocks.next())),
+        None => Err(QueensError::EmptyLayout),
+    }
+}
+
+// Check that a block is an n by n square
+fn check_square(block: &Block, n: usize) -> QueensResult<()> {
+    for (line, row) in block {
+        if row.len() != n {
+            return Err(QueensError::RaggedRow {
+                line: *line,
+                expected: n,
+                found: row.len(),
+            });
+        }
+    }
+
+    if block.len() != n {
+        return Err(QueensError::WrongRowCount {
+            expected: n,
+            found: block.len(),
+        });
+    }
+
+    Ok(())
+}
+
+fn parse_layout(block: &Block) -> QueensResult<Layout> {
+    let n = block[0].1.len();
+    check_square(block, n)?;
+
+    let mut symbols: Vec<char> = Vec::new();
+    for (line, row) in block {
+        for (column, &c) in row.iter().enumerate() {
+            if !c.is_ascii_alphanumeric() {
+                return Err(QueensError::InvalidSymbol {
+                    line: *line,
+                    column: column + 1,
+                    symbol: c,
+                });
+            }
+            if !symbols.contains(&c) {
+                symbols.push(c);
+            }
+        }
+    }
+
+    if symbols.len() != n {
+        return Err(QueensError::WrongAreaCount {
+            expected: n,
+            found: symbols.len(),
+        });
+    }
+
+    // Colors follow the order of the symbols, so printed layouts parse to the same colors
+    symbols.sort();
+
+    let tiles: Vec<char> = block.iter().flat_map(|(_, row)| row.clone()).collect();
+    let mut areas = Vec::new();
+
+    for (color, &s) in symbols.iter().enumerate() {
+        let indices: Vec<usize> = (0..tiles.len()).filter(|&c| tiles[c] == s).collect();
+
+        if connected(&indices, indices[0], n).len() != indices.len() {
+            return Err(QueensError::NonContiguousArea { symbol: s });
+        }
+
+        areas.push(Area::from_sections(
+            indices.into_iter().map(section).collect(),
+            color as u8,
+        ));
+    }
+
+    Ok(Layout::new(areas, n))
+}
+
+impl FromStr for Layout {
+    type Err = QueensError;
+
+    // Parses the layout block, any state block is ignored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (layout, _) = blocks(s)?;
+
+        parse_layout(&layout)
+    }
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let n = self.get_n();
+        let mut symbols = vec!['?'; n.pow(2)];
+
+        for area in self.get_areas() {
+            for &c in area.get_sections() {
+                if let Some(s) = symbols.get_mut(c) {
+                    *s = symbol(area.get_color());
+                }
+            }
+        }
+
+        for row in symbols.chunks(n.max(1)) {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Grid {
+    type Err = QueensError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (layout, state) = blocks(s)?;
+        let layout = parse_layout(&layout)?;
+        let mut grid = Grid::from_layout(&layout);
+        let n = layout.get_n();
+
+        if let Some(state) = state {
+            check_square(&state, n)?;
+
+            for (r, (line, row)) in state.iter().enumerate() {
+                for (col, &c) in row.iter().enumerate() {
+                    let tile_state = match c {
+                        EMPTY => TileState::Empty,
+                        CROSSED => TileState::Crossed,
+                        QUEEN => TileState::Queen,
+                        _ => {
+                            return Err(QueensError::InvalidSymbol {
+                                line: *line,
+                                column: col + 1,
+                                symbol: c,
+                            })
+                        }
+                    };
+
+                    let coord = Coord { row: r, col };
+                    let color = grid.get_tile(coord)?.get_raw_color();
+                    grid.set_tile(coord, Tile::new(tile_state, color))?;
+                }
+            }
+        }
+
+        Ok(grid)
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let n = self.get_n().max(1);
+
+        for row in self.get_data().chunks(n) {
+            let line: String = row.iter().map(|t| symbol(t.get_raw_color())).collect();
+            writeln!(f, "{}", line)?;
+        }
+
+        writeln!(f)?;
+
+        for row in self.get_data().chunks(n) {
+            let line: String = row
+                .iter()
+                .map(|t| match t.get_state() {
+                    TileState::Empty => EMPTY,
+                    TileState::Crossed | TileState::AutoCrossed => CROSSED,
+                    TileState::Queen => QUEEN,
+                })
+                .collect();
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_round_trip() {
+        let layouts = [
+            Layout::complex_layout(10).unwrap(),
+            Layout::easy_layout(6).unwrap(),
+            Layout::generate_layout(9, 1).unwrap(),
+            Layout::generate_unique_layout(7, 2).unwrap(),
+        ];
+
+        for layout in layouts {
+            let text = layout.to_string();
+            assert_eq!(text.parse::<Layout>().unwrap(), layout);
+        }
+    }
+
+    #[test]
+    fn layout_text() {
+        let text = "AABBB\nACCCB\nADDCB\nADEEB\nAAEEE\n";
+        assert_eq!(text.parse::<Layout>().unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn grid_round_trip() {
+        let mut grid = Grid::from_layout(&Layout::complex_layout(10).unwrap());
+        for (row, col, state) in [
+            (0, 0, TileState::Queen),
+            (0, 5, TileState::Crossed),
+            (9, 9, TileState::Queen),
+        ] {
+            let coord = Coord { row, col };
+            let color = grid.get_tile(coord).unwrap().get_raw_color();
+            grid.set_tile(coord, Tile::new(state, color)).unwrap();
+        }
+
+        let text = grid.to_string();
+        assert_eq!(text.parse::<Grid>().unwrap(), grid);
+    }
+
+    #[test]
+    fn ragged_row() {
+        assert!(matches!(
+            "AAB\nAB\nCCC".parse::<Layout>(),
+            Err(QueensError::RaggedRow {
+                line: 2,
+                expected: 3,
+                found: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_symbol() {
+        assert!(matches!(
+            "AAB\nAB-\nCCC".parse::<Layout>(),
+            Err(QueensError::InvalidSymbol {
+                line: 2,
+                column: 3,
+                symbol: '-'
+            })
+        ));
+        assert!(matches!(
+            "AAB\nABB\nCCC\n\n..Q\n.?.\n...".parse::<Grid>(),
+            Err(QueensError::InvalidSymbol {
+                line: 6,
+                column: 2,
+                symbol: '?'
+            })
+        ));
+    }
+
+    #[test]
+    fn empty_layout() {
+        assert!(matches!(
+            "".parse::<Layout>(),
+            Err(QueensError::EmptyLayout)
+        ));
+        assert!(matches!(
+            " \n\n".parse::<Grid>(),
+            Err(QueensError::EmptyLayout)
+        ));
+    }
+}
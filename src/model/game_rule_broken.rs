@@ -1,29 +1,259 @@
-use crate::model::layout::Area;
+use crate::errors::QueensResult;
+use crate::model::board::{Bitboard, Board};
+use crate::model::coord::Coord;
+use crate::model::grid::Grid;
+use crate::model::layout::{Area, Layout};
 use std::fmt::{Display, Formatter};
 
 #[derive(Clone, Debug)]
 pub enum GameRuleBroken {
-    Row { row: usize },
-    Column { col: usize },
-    Area { area: Area },
-    Diagonal { c1: usize, c2: usize }, // todo add coordinate struct
+    Row { row: usize, queens: Vec<Coord> },
+    Column { col: usize, queens: Vec<Coord> },
+    Area { area: Area, queens: Vec<Coord> },
+    // Two queens next to each other, diagonals included
+    Touching { c1: Coord, c2: Coord },
+}
+
+impl GameRuleBroken {
+    // The tiles breaking the rule, to point them out on the grid
+    pub fn tiles(&self, n: usize) -> Vec<Coord> {
+        match self {
+            GameRuleBroken::Row { row, .. } => (0..n).map(|col| Coord { row: *row, col }).collect(),
+            GameRuleBroken::Column { col, .. } => {
+                (0..n).map(|row| Coord { row, col: *col }).collect()
+            }
+            GameRuleBroken::Area { area, .. } => area.get_coords(n),
+            GameRuleBroken::Touching { c1, c2 } => vec![*c1, *c2],
+        }
+    }
+
+    // The queens breaking the rule
+    pub fn get_queens(&self) -> Vec<Coord> {
+        match self {
+            GameRuleBroken::Row { queens, .. }
+            | GameRuleBroken::Column { queens, .. }
+            | GameRuleBroken::Area { queens, .. } => queens.clone(),
+            GameRuleBroken::Touching { c1, c2 } => vec![*c1, *c2],
+        }
+    }
+}
+
+fn format_queens(queens: &[Coord]) -> String {
+    queens
+        .iter()
+        .map(|coord| coord.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl Display for GameRuleBroken {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            GameRuleBroken::Row { row } => {
-                write!(f, "Row {} has duplicates!!", row)
+            GameRuleBroken::Row { row, queens } => {
+                write!(f, "Row {} has queens at {}", row, format_queens(queens))
             }
-            GameRuleBroken::Column { col } => {
-                write!(f, "Column {} has duplicates", col)
+            GameRuleBroken::Column { col, queens } => {
+                write!(f, "Column {} has queens at {}", col, format_queens(queens))
             }
-            GameRuleBroken::Area { area } => {
-                write!(f, "Duplicates in area {}", area.get_color())
+            GameRuleBroken::Area { area, queens } => write!(
+                f,
+                "Area {} has queens at {}",
+                area.get_color(),
+                format_queens(queens)
+            ),
+            GameRuleBroken::Touching { c1, c2 } => {
+                write!(f, "Queens at {} and {} touch", c1, c2)
             }
-            GameRuleBroken::Diagonal { c1, c2 } => {
-                write!(f, "Diagonal problem at {} and {}", c1, c2)
+        }
+    }
+}
+
+// Get the list of broken rules and whether the grid solves the layout
+pub fn check_rules(layout: &Layout, grid: &Grid) -> QueensResult<(Vec<GameRuleBroken>, bool)> {
+    check_rules_on(&Board::new(layout)?, layout, grid)
+}
+
+// As check_rules, with the board of the layout already built
+pub fn check_rules_on(
+    board: &Board,
+    layout: &Layout,
+    grid: &Grid,
+) -> QueensResult<(Vec<GameRuleBroken>, bool)> {
+    let n = grid.get_n();
+    let mut errors = Vec::new();
+
+    let mut queens = Bitboard::new();
+    for (c, tile) in grid.get_data().iter().enumerate() {
+        if tile.is_queen() {
+            queens.insert(c);
+        }
+    }
+    let coords = |tiles: Bitboard| -> QueensResult<Vec<Coord>> {
+        tiles.iter().map(|c| grid.get_coord(c)).collect()
+    };
+
+    // Each row, column and area once, with all of its queens
+    for (row, tiles) in board.get_rows().iter().enumerate() {
+        let found = *tiles & queens;
+        if found.count() > 1 {
+            errors.push(GameRuleBroken::Row {
+                row,
+                queens: coords(found)?,
+            });
+        }
+    }
+    for (col, tiles) in board.get_cols().iter().enumerate() {
+        let found = *tiles & queens;
+        if found.count() > 1 {
+            errors.push(GameRuleBroken::Column {
+                col,
+                queens: coords(found)?,
+            });
+        }
+    }
+    for (area, tiles) in layout.get_areas().iter().zip(board.get_areas()) {
+        let found = *tiles & queens;
+        if found.count() > 1 {
+            errors.push(GameRuleBroken::Area {
+                area: area.clone(),
+                queens: coords(found)?,
+            });
+        }
+    }
+
+    // All eight neighbours, each pair once
+    for c1 in queens.iter() {
+        for c2 in (board.get_neighbours(c1) & queens)
+            .iter()
+            .filter(|&c2| c2 > c1)
+        {
+            errors.push(GameRuleBroken::Touching {
+                c1: grid.get_coord(c1)?,
+                c2: grid.get_coord(c2)?,
+            });
+        }
+    }
+
+    // Without broken rules, n queens fill every row, column and area
+    let won = errors.is_empty() && queens.count() == n && board.get_areas().len() == n;
+    Ok((errors, won))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::solver::Solver;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+    use std::collections::BTreeMap;
+
+    // The checker before the bitboards, grouping the queens by row, column and color
+    fn check_rules_grouped(layout: &Layout, grid: &Grid) -> (Vec<GameRuleBroken>, bool) {
+        let n = grid.get_n();
+        let mut errors = Vec::new();
+        let mut rows: BTreeMap<usize, Vec<Coord>> = BTreeMap::new();
+        let mut cols: BTreeMap<usize, Vec<Coord>> = BTreeMap::new();
+        let mut colors: BTreeMap<u8, Vec<Coord>> = BTreeMap::new();
+        let mut queens = Vec::new();
+
+        for (index, tile) in grid.get_data().iter().enumerate() {
+            if tile.is_queen() {
+                let coord = grid.get_coord(index).unwrap();
+                rows.entry(coord.row).or_default().push(coord);
+                cols.entry(coord.col).or_default().push(coord);
+                colors.entry(tile.get_raw_color()).or_default().push(coord);
+                queens.push(coord);
+            }
+        }
+
+        for (&row, queens) in rows.iter().filter(|(_, q)| q.len() > 1) {
+            let queens = queens.clone();
+            errors.push(GameRuleBroken::Row { row, queens });
+        }
+        for (&col, queens) in cols.iter().filter(|(_, q)| q.len() > 1) {
+            let queens = queens.clone();
+            errors.push(GameRuleBroken::Column { col, queens });
+        }
+        for queens in colors.values().filter(|q| q.len() > 1) {
+            let area = layout.get_area_at(queens[0]).unwrap().clone();
+            let queens = queens.clone();
+            errors.push(GameRuleBroken::Area { area, queens });
+        }
+        for (i, c1) in queens.iter().enumerate() {
+            for c2 in queens[i + 1..].iter().filter(|c2| c1.touches(c2)) {
+                errors.push(GameRuleBroken::Touching { c1: *c1, c2: *c2 });
             }
         }
+
+        let won = rows.len() == n && cols.len() == n && colors.len() == n && errors.is_empty();
+        (errors, won)
+    }
+
+    // Broken rules in a fixed order, as the checkers list areas differently
+    fn sorted(errors: &[GameRuleBroken]) -> Vec<String> {
+        let mut res: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        res.sort();
+        res
+    }
+
+    fn place_queens(layout: &Layout, queens: &[usize]) -> Grid {
+        let mut grid = Grid::from_layout(layout);
+        for &c in queens {
+            grid.place_queen(grid.get_coord(c).unwrap()).unwrap();
+        }
+        grid
+    }
+
+    fn assert_same(layout: &Layout, grid: &Grid) {
+        let (errors, won) = check_rules(layout, grid).unwrap();
+        let (expected, expected_won) = check_rules_grouped(layout, grid);
+
+        assert_eq!(sorted(&errors), sorted(&expected));
+        assert_eq!(won, expected_won);
+    }
+
+    #[test]
+    fn matches_the_grouped_checker_on_random_queens() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        for n in [5, 8, 10] {
+            let layout = Layout::generate_layout(n, n as u64).unwrap();
+            for _ in 0..50 {
+                let count = rng.random_range(0..=2 * n);
+                let queens: Vec<usize> =
+                    (0..count).map(|_| rng.random_range(0..n.pow(2))).collect();
+                assert_same(&layout, &place_queens(&layout, &queens));
+            }
+        }
+    }
+
+    #[test]
+    fn matches_the_grouped_checker_on_solutions() {
+        for seed in 0..5 {
+            let layout = Layout::generate_unique_layout(8, seed).unwrap();
+            let solution = &Solver::new(&layout, 8).unwrap().solutions(Some(1))[0];
+            let grid = place_queens(&layout, solution);
+
+            assert!(check_rules(&layout, &grid).unwrap().1);
+            assert_same(&layout, &grid);
+            assert_same(&layout, &place_queens(&layout, &solution[1..]));
+        }
+    }
+
+    #[test]
+    fn finds_every_broken_rule() {
+        let layout = Layout::easy_layout(5).unwrap();
+        // Two queens touching diagonally and two in a column
+        let grid = place_queens(&layout, &[0, 6, 16]);
+        let (errors, won) = check_rules(&layout, &grid).unwrap();
+
+        assert!(!won);
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, GameRuleBroken::Touching { .. })));
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, GameRuleBroken::Column { col: 1, .. })));
+        assert_same(&layout, &grid);
     }
 }
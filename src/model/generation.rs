@@ -0,0 +1,148 @@
+use crate::errors::{QueensError, QueensResult};
+use crate::model::layout::LayoutType;
+use crate::model::state::InGameState;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// New game being generated on a background thread, so big grids do not freeze the window.
+// Dropping it cancels a unique or rated generation at its next round of new areas.
+pub struct Generation {
+    n: usize,
+    layout_type: LayoutType,
+    receiver: Mutex<Receiver<QueensResult<InGameState>>>,
+    // Layouts tried so far by a rated generation
+    attempts: Arc<AtomicUsize>,
+    cancelled: Arc<AtomicBool>,
+    started: Instant,
+}
+
+impl Generation {
+    pub fn start(n: usize, layout_type: LayoutType, seed: u64) -> Generation {
+        let (sender, receiver) = mpsc::channel();
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let (thread_attempts, thread_cancelled) = (attempts.clone(), cancelled.clone());
+        let thread_type = layout_type.clone();
+        thread::spawn(move || {
+            let res = InGameState::generate(n, thread_type, seed, &mut |attempt| {
+                thread_attempts.store(attempt, Ordering::Relaxed);
+                !thread_cancelled.load(Ordering::Relaxed)
+            });
+            // nobody is waiting anymore once the generation is dropped
+            let _ = sender.send(res);
+        });
+
+        Generation {
+            n,
+            layout_type,
+            receiver: Mutex::new(receiver),
+            attempts,
+            cancelled,
+            started: Instant::now(),
+        }
+    }
+
+    // The game once it is generated
+    pub fn poll(&self) -> Option<QueensResult<InGameState>> {
+        let receiver = self.receiver.lock().unwrap_or_else(|e| e.into_inner());
+
+        match receiver.try_recv() {
+            Ok(res) => Some(res),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(QueensError::GenerationFailed)),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn get_n(&self) -> usize {
+        self.n
+    }
+
+    pub fn get_layout_type(&self) -> &LayoutType {
+        &self.layout_type
+    }
+
+    // Layouts tried so far, none unless the layout is rated
+    pub fn get_attempts(&self) -> Option<usize> {
+        match self.layout_type {
+            LayoutType::Rated(_) => Some(self.attempts.load(Ordering::Relaxed)),
+            _ => None,
+        }
+    }
+
+    pub fn get_elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+impl Drop for Generation {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::difficulty::Difficulty;
+    use crate::model::layout::Layout;
+
+    fn wait(generation: &Generation) -> QueensResult<InGameState> {
+        loop {
+            if let Some(res) = generation.poll() {
+                return res;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn generates_in_the_background() {
+        let generation = Generation::start(8, LayoutType::GeneratedUnique, 4);
+        let in_game = wait(&generation).unwrap();
+
+        assert_eq!(in_game.get_layout().get_n(), 8);
+        assert_eq!(in_game.get_seed(), Some(4));
+        assert_eq!(generation.get_attempts(), None);
+    }
+
+    #[test]
+    fn reports_errors() {
+        let generation = Generation::start(10, LayoutType::Easy, 0);
+        assert!(wait(&generation).is_ok());
+
+        let generation = Generation::start(17, LayoutType::Complex, 0);
+        assert!(matches!(
+            wait(&generation),
+            Err(QueensError::UnsupportedSize { n: 17, .. })
+        ));
+    }
+
+    #[test]
+    fn rated_generation_stops_when_asked() {
+        let res = Layout::generate_rated_layout_with(8, Difficulty::Expert, 0, &mut |_| false);
+        assert!(matches!(res, Err(QueensError::GenerationCancelled)));
+
+        let mut tried = Vec::new();
+        let _ = Layout::generate_rated_layout_with(8, Difficulty::Expert, 0, &mut |attempt| {
+            tried.push(attempt);
+            attempt < 3
+        });
+        // each layout can take a few rounds of new areas
+        tried.dedup();
+        assert_eq!(tried, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn unique_generation_stops_when_asked() {
+        let res = Layout::generate_unique_layout_with(16, 0, &mut || false);
+        assert!(matches!(res, Err(QueensError::GenerationCancelled)));
+    }
+}
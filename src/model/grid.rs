@@ -1,10 +1,11 @@
-use crate::errors::{QueensError, QueensResult};
-use crate::model::tile::Tile;
-use std::fmt;
-use std::fmt::{Display, Formatter};
+use crate::errors::QueensResult;
+use crate::model::coord::Coord;
+use crate::model::layout::Layout;
+use crate::model::tile::{Tile, TileState};
+use serde::{Deserialize, Serialize};
 
 // 0-indexed square matrix of tiles.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Grid {
     data: Vec<Tile>,
     n: usize,
@@ -18,41 +19,51 @@ impl Grid {
         Grid { data, n }
     }
 
-    pub fn get_tile(&self, index: usize) -> QueensResult<Tile> {
-        match self.data.get(index) {
-            Some(tile) => Ok(*tile),
-            None => Err(QueensError::OutOfBounds { c: index }),
+    // Empty grid colored by the areas of a layout
+    pub fn from_layout(layout: &Layout) -> Grid {
+        let mut grid = Grid::new(layout.get_n());
+
+        for area in layout.get_areas() {
+            for &index in area.get_sections() {
+                if let Some(tile) = grid.data.get_mut(index) {
+                    *tile = Tile::new(TileState::Empty, area.get_color());
+                }
+            }
         }
-    }
 
-    pub fn set_tile(&mut self, index: usize, tile: Tile) {
-        self.data[index] = tile;
+        grid
     }
 
-    pub fn get_data(&self) -> &Vec<Tile> {
-        &self.data
+    pub fn get_tile(&self, coord: Coord) -> QueensResult<Tile> {
+        Ok(self.data[self.get_index(coord)?])
     }
 
-    // Gives (col, row)
-    pub fn split_coordinate(&self, c: usize) -> QueensResult<(usize, usize)> {
-        let n = self.n;
+    pub fn set_tile(&mut self, coord: Coord, tile: Tile) -> QueensResult<()> {
+        let index = self.get_index(coord)?;
+        self.data[index] = tile;
+        Ok(())
+    }
 
-        if c >= n.pow(2) {
-            return Err(QueensError::OutOfBounds { c });
-        }
+    // Put a queen on a tile, keeping its color
+    pub fn place_queen(&mut self, coord: Coord) -> QueensResult<()> {
+        let color = self.get_tile(coord)?.get_raw_color();
+        self.set_tile(coord, Tile::new(TileState::Queen, color))
+    }
 
-        let col = c % n;
-        let row = c.div_euclid(n);
+    pub fn get_n(&self) -> usize {
+        self.n
+    }
 
-        Ok((col, row))
+    pub fn get_data(&self) -> &Vec<Tile> {
+        &self.data
     }
 
-    pub fn merge_coordinate(&self, column: usize, row: usize) -> QueensResult<usize> {
-        let n = self.n;
-        if column >= n || row >= n {
-            return Err(QueensError::Invalid2DCoordinate { column, row, n });
-        }
+    pub fn get_coord(&self, index: usize) -> QueensResult<Coord> {
+        Coord::from_index(index, self.n)
+    }
 
-        Ok(row * n + column)
+    // Index of a coordinate, checked to be on the grid
+    pub fn get_index(&self, coord: Coord) -> QueensResult<usize> {
+        Ok(Coord::new(coord.row, coord.col, self.n)?.get_index(self.n))
     }
 }
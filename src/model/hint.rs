@@ -0,0 +1,212 @@
+use crate::errors::QueensResult;
+use crate::model::coord::Coord;
+use crate::model::deduction::{Deducer, Step, Technique};
+use crate::model::grid::Grid;
+use crate::model::layout::Layout;
+use crate::model::solver::{Solution, Solver};
+use std::fmt::{Display, Formatter};
+
+// Solutions to compare the player's marks against when a layout has several
+const SOLUTIONS_CONSIDERED: usize = 100;
+
+// Help for a stuck player
+#[derive(Clone, Debug)]
+pub enum Hint {
+    // The next deduction the player can make
+    Step(Step),
+    // Queens and crosses that no solution agrees with
+    Mistakes { tiles: Vec<Coord> },
+    // The techniques get stuck, so only the queen itself can help
+    NoDeduction,
+}
+
+impl Hint {
+    pub fn find(layout: &Layout, grid: &Grid) -> QueensResult<Hint> {
+        let mistakes = Self::mistakes(layout, grid)?;
+        if !mistakes.is_empty() {
+            return Ok(Hint::Mistakes { tiles: mistakes });
+        }
+
+        let mut deducer = Deducer::from_grid(layout, grid)?;
+
+        // What a placed queen rules out is no news to the player
+        while let Some(step) = deducer.next_step() {
+            if step.explanation.technique() != Technique::PlacedQueen {
+                return Ok(Hint::Step(step));
+            }
+            deducer.apply(&step);
+        }
+
+        Ok(Hint::NoDeduction)
+    }
+
+    // The queen to place for a stronger hint: the first one the deductions force, otherwise the
+    // solution's queen in the smallest open row, column or area.
+    pub fn next_queen(layout: &Layout, grid: &Grid) -> QueensResult<Option<Coord>> {
+        let mut deducer = Deducer::from_grid(layout, grid)?;
+
+        while let Some(step) = deducer.next_step() {
+            if let Some(&c) = step.queens.first() {
+                return Ok(Some(c));
+            }
+            deducer.apply(&step);
+        }
+
+        let queens = Self::placed(grid);
+        let solution = match Self::closest_solution(layout, grid)? {
+            Some(solution) => solution,
+            None => return Ok(None),
+        };
+
+        deducer
+            .most_constrained()
+            .and_then(|tiles| tiles.into_iter().find(|c| solution.contains(c)))
+            .or_else(|| solution.into_iter().find(|c| !queens.contains(c)))
+            .map(|c| grid.get_coord(c))
+            .transpose()
+    }
+
+    fn placed(grid: &Grid) -> Vec<usize> {
+        (0..grid.get_data().len())
+            .filter(|&c| grid.get_data()[c].is_queen())
+            .collect()
+    }
+
+    fn crossed(grid: &Grid) -> Vec<usize> {
+        (0..grid.get_data().len())
+            .filter(|&c| grid.get_data()[c].is_crossed())
+            .collect()
+    }
+
+    // The solution agreeing with the most queens and crosses on the grid
+    fn closest_solution(layout: &Layout, grid: &Grid) -> QueensResult<Option<Solution>> {
+        let queens = Self::placed(grid);
+        let crossed = Self::crossed(grid);
+        let solutions = Solver::new(layout, grid.get_n())?.solutions(Some(SOLUTIONS_CONSIDERED));
+
+        Ok(solutions.into_iter().max_by_key(|s| {
+            queens.iter().filter(|c| s.contains(c)).count()
+                + crossed.iter().filter(|c| !s.contains(c)).count()
+        }))
+    }
+
+    fn mistakes(layout: &Layout, grid: &Grid) -> QueensResult<Vec<Coord>> {
+        let queens = Self::placed(grid);
+        let crossed = Self::crossed(grid);
+        let solver = Solver::new(layout, grid.get_n())?;
+
+        if !solver.solutions_with(&queens, &crossed, Some(1)).is_empty() {
+            return Ok(Vec::new());
+        }
+
+        match Self::closest_solution(layout, grid)? {
+            Some(solution) => queens
+                .into_iter()
+                .filter(|c| !solution.contains(c))
+                .chain(crossed.into_iter().filter(|c| solution.contains(c)))
+                .map(|c| grid.get_coord(c))
+                .collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hint::Step(step) => write!(f, "{}", step),
+            Hint::Mistakes { tiles } => {
+                write!(
+                    f,
+                    "{} of your queens and crosses cannot be part of a solution",
+                    tiles.len()
+                )
+            }
+            Hint::NoDeduction => write!(f, "No deduction left, ask for a queen"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::tile::{Tile, TileState};
+
+    // The "First steps" level, solved by (0, 2), (1, 4), (2, 0), (3, 3) and (4, 1)
+    fn layout() -> Layout {
+        "BBBBB\nBBBBE\nCBBBA\nCCAAA\nDDAAA".parse().unwrap()
+    }
+
+    fn coord(row: usize, col: usize) -> Coord {
+        Coord { row, col }
+    }
+
+    fn cross(grid: &mut Grid, coord: Coord) {
+        let color = grid.get_tile(coord).unwrap().get_raw_color();
+        grid.set_tile(coord, Tile::new(TileState::Crossed, color))
+            .unwrap();
+    }
+
+    #[test]
+    fn gives_the_next_deduction() {
+        let layout = layout();
+        let mut grid = Grid::from_layout(&layout);
+
+        let Hint::Step(step) = Hint::find(&layout, &grid).unwrap() else {
+            panic!("expected a deduction");
+        };
+        assert_ne!(step.explanation.technique(), Technique::PlacedQueen);
+
+        // What a placed queen rules out is skipped
+        grid.place_queen(coord(1, 4)).unwrap();
+        let Hint::Step(step) = Hint::find(&layout, &grid).unwrap() else {
+            panic!("expected a deduction");
+        };
+        assert_ne!(step.explanation.technique(), Technique::PlacedQueen);
+        assert!(!step.eliminated.contains(&coord(0, 4)));
+    }
+
+    #[test]
+    fn points_out_mistakes_first() {
+        let layout = layout();
+        let mut grid = Grid::from_layout(&layout);
+        grid.place_queen(coord(0, 0)).unwrap();
+        cross(&mut grid, coord(2, 0));
+        // Right marks are left out
+        grid.place_queen(coord(1, 4)).unwrap();
+        cross(&mut grid, coord(4, 4));
+
+        let Hint::Mistakes { tiles } = Hint::find(&layout, &grid).unwrap() else {
+            panic!("expected mistakes");
+        };
+        assert_eq!(tiles, vec![coord(0, 0), coord(2, 0)]);
+    }
+
+    #[test]
+    fn nothing_left_once_solved() {
+        let layout = layout();
+        let mut grid = Grid::from_layout(&layout);
+        for (row, col) in [(0, 2), (1, 4), (2, 0), (3, 3), (4, 1)] {
+            grid.place_queen(coord(row, col)).unwrap();
+        }
+
+        assert!(matches!(
+            Hint::find(&layout, &grid).unwrap(),
+            Hint::NoDeduction
+        ));
+        assert_eq!(Hint::next_queen(&layout, &grid).unwrap(), None);
+    }
+
+    #[test]
+    fn next_queen_is_part_of_the_solution() {
+        let layout = layout();
+        let solution = [(0, 2), (1, 4), (2, 0), (3, 3), (4, 1)].map(|(r, c)| coord(r, c));
+        let mut grid = Grid::from_layout(&layout);
+
+        for _ in 0..solution.len() {
+            let queen = Hint::next_queen(&layout, &grid).unwrap().unwrap();
+            assert!(solution.contains(&queen));
+            grid.place_queen(queen).unwrap();
+        }
+    }
+}
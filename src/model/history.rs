@@ -0,0 +1,162 @@
+use crate::model::coord::Coord;
+use crate::model::tile::Tile;
+use serde::{Deserialize, Serialize};
+
+// A single tile change
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Move {
+    pub coord: Coord,
+    pub before: Tile,
+    pub after: Tile,
+}
+
+// Undo and redo stacks of moves. Moves made together, like an automatic multi-tile operation,
+// are batched so they are undone as one step.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    undo: Vec<Vec<Move>>,
+    redo: Vec<Vec<Move>>,
+    batch: Option<Vec<Move>>,
+    // Batches can be nested, only the outermost one ends up as a step
+    batch_depth: usize,
+}
+
+impl History {
+    pub fn record(&mut self, m: Move) {
+        match &mut self.batch {
+            Some(batch) => batch.push(m),
+            None => self.undo.push(vec![m]),
+        }
+        self.redo.clear();
+    }
+
+    // Record the following moves as one step until end_batch is called
+    pub fn begin_batch(&mut self) {
+        if self.batch.is_none() {
+            self.batch = Some(Vec::new());
+        }
+        self.batch_depth += 1;
+    }
+
+    pub fn end_batch(&mut self) {
+        self.batch_depth = self.batch_depth.saturating_sub(1);
+        if self.batch_depth > 0 {
+            return;
+        }
+
+        if let Some(batch) = self.batch.take() {
+            if !batch.is_empty() {
+                self.undo.push(batch);
+            }
+        }
+    }
+
+    // Gives the moves to revert, latest first
+    pub fn undo(&mut self) -> Option<Vec<Move>> {
+        let moves = self.undo.pop()?;
+        self.redo.push(moves.clone());
+
+        Some(moves.into_iter().rev().collect())
+    }
+
+    // Gives the moves to make again, in their original order
+    pub fn redo(&mut self) -> Option<Vec<Move>> {
+        let moves = self.redo.pop()?;
+        self.undo.push(moves.clone());
+
+        Some(moves)
+    }
+
+    // Every move recorded, to undo, to redo or in the open batch
+    pub fn get_moves(&self) -> impl Iterator<Item = &Move> {
+        self.undo
+            .iter()
+            .chain(self.redo.iter())
+            .chain(self.batch.iter())
+            .flatten()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::tile::TileState;
+
+    fn queen_at(col: usize) -> Move {
+        Move {
+            coord: Coord { row: 0, col },
+            before: Tile::default(),
+            after: Tile::new(TileState::Queen, 0),
+        }
+    }
+
+    fn cols(moves: &[Move]) -> Vec<usize> {
+        moves.iter().map(|m| m.coord.col).collect()
+    }
+
+    #[test]
+    fn nested_batches_are_one_step() {
+        let mut history = History::default();
+
+        history.begin_batch();
+        history.record(queen_at(0));
+        history.begin_batch();
+        history.record(queen_at(1));
+        history.end_batch();
+        history.record(queen_at(2));
+        history.end_batch();
+
+        assert_eq!(cols(&history.undo().unwrap()), vec![2, 1, 0]);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn empty_batches_are_not_recorded() {
+        let mut history = History::default();
+
+        history.begin_batch();
+        history.end_batch();
+
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undo_reverses_and_redo_replays_in_order() {
+        let mut history = History::default();
+        history.record(queen_at(0));
+        history.begin_batch();
+        history.record(queen_at(1));
+        history.record(queen_at(2));
+        history.end_batch();
+
+        assert_eq!(cols(&history.undo().unwrap()), vec![2, 1]);
+        assert_eq!(cols(&history.undo().unwrap()), vec![0]);
+        assert!(history.undo().is_none());
+
+        assert_eq!(cols(&history.redo().unwrap()), vec![0]);
+        assert_eq!(cols(&history.redo().unwrap()), vec![1, 2]);
+        assert!(history.redo().is_none());
+    }
+
+    #[test]
+    fn new_moves_clear_redo() {
+        let mut history = History::default();
+        history.record(queen_at(0));
+        history.undo();
+        assert!(history.can_redo());
+
+        history.record(queen_at(1));
+
+        assert!(!history.can_redo());
+        assert_eq!(cols(&history.undo().unwrap()), vec![1]);
+        assert!(!history.can_undo());
+    }
+}
@@ -1,8 +1,35 @@
 use crate::errors::{QueensError, QueensResult};
-use rand::prelude::SliceRandom;
-use rand::Rng;
+use crate::model::board::{MAX_N, MIN_N};
+use crate::model::coord::Coord;
+use crate::model::difficulty::{Difficulty, Rating};
+use crate::model::layout_problem::LayoutProblem;
+use crate::model::solver::Solver;
+use rand::prelude::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
 use std::ops::{Range, RangeInclusive};
 
+// Unique layouts to try before settling for a different difficulty
+pub const RATING_ATTEMPTS: usize = 200;
+
+// Largest grid tried RATING_ATTEMPTS times. A unique layout of 16 can take seconds to generate, so
+// each size above this halves the attempts and settles for the closest difficulty sooner.
+const FULL_RATING_N: usize = 12;
+
+// Unique layouts to try on a grid of size n before settling for a different difficulty
+pub fn rating_attempts(n: usize) -> usize {
+    RATING_ATTEMPTS >> n.saturating_sub(FULL_RATING_N)
+}
+
+// Rounds of new areas to grow before giving up on a unique layout. Even a grid of 16 rarely
+// needs more than a few dozen.
+const UNIQUE_ROUNDS: usize = 1000;
+
+// One template per size in the text format, separated by blank lines
+const COMPLEX_LAYOUTS: &str = include_str!("../../templates/complex.txt");
+
 // A set of contiguous indices on the grid
 #[derive(Clone, Debug)]
 pub struct Section {
@@ -36,7 +63,7 @@ pub fn section<R: Into<Section>>(c: R) -> Section {
 }
 
 // Colored area of layout
-#[derive(Eq, Hash, PartialEq, Clone, Debug, Default)]
+#[derive(Eq, Hash, PartialEq, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Area {
     sections: Vec<usize>,
     color: u8,
@@ -69,51 +96,151 @@ impl Area {
     pub fn get_color(&self) -> u8 {
         self.color
     }
+
+    pub fn contains(&self, coord: Coord, n: usize) -> bool {
+        coord.row < n && coord.col < n && self.sections.contains(&coord.get_index(n))
+    }
+
+    pub fn get_coords(&self, n: usize) -> Vec<Coord> {
+        self.sections
+            .iter()
+            .filter_map(|&c| Coord::from_index(c, n).ok())
+            .collect()
+    }
 }
 
 // Layout of multiple areas
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Layout {
     areas: Vec<Area>,
+    n: usize,
 }
 
 impl Layout {
-    fn new(areas: Vec<Area>) -> Layout {
-        Self { areas }
+    pub fn new(areas: Vec<Area>, n: usize) -> Layout {
+        Self { areas, n }
     }
 
     pub fn get_areas(&self) -> &Vec<Area> {
         &self.areas
     }
 
-    fn from_sections(sections: Vec<Vec<Section>>, n: usize) -> Layout {
-        if sections.len() != n {
-            panic!("{} sections cannot cover {} queens", sections.len(), n)
+    pub fn get_n(&self) -> usize {
+        self.n
+    }
+
+    // Colors of the areas covering each tile
+    fn tile_colors(&self) -> Vec<Vec<u8>> {
+        let mut res = vec![Vec::new(); self.n.pow(2)];
+        for area in self.areas.iter() {
+            for &c in area.get_sections() {
+                if let Some(colors) = res.get_mut(c) {
+                    colors.push(area.get_color());
+                }
+            }
         }
+        for colors in res.iter_mut() {
+            colors.sort();
+        }
+        res
+    }
 
+    fn from_sections(sections: Vec<Vec<Section>>, n: usize) -> QueensResult<Layout> {
         let areas = sections
             .iter()
             .enumerate()
             .map(|(i, secs)| Area::from_sections(secs.clone(), i as u8))
             .collect::<Vec<Area>>();
 
-        // todo we do not check for overlap right now
+        let layout = Self::new(areas, n);
+        let problems = layout.validate();
 
-        Self { areas }
+        if problems.is_empty() {
+            Ok(layout)
+        } else {
+            Err(QueensError::InvalidLayout { problems })
+        }
     }
 
-    pub fn get_area(&self, index: usize) -> QueensResult<Area> {
-        for a in self.areas.iter() {
-            if a.sections.contains(&index) {
-                return Ok(a.clone());
+    // Every reason the layout cannot be played, empty if it is valid.
+    // A valid layout has n contiguous areas of distinct colors covering each tile exactly once.
+    pub fn validate(&self) -> Vec<LayoutProblem> {
+        let n = self.n;
+        let mut problems = Vec::new();
+        let mut owners: Vec<Vec<u8>> = vec![Vec::new(); n.pow(2)];
+
+        if self.areas.len() != n {
+            problems.push(LayoutProblem::WrongAreaCount {
+                expected: n,
+                found: self.areas.len(),
+            });
+        }
+
+        let mut colors = Vec::new();
+        for area in self.areas.iter() {
+            let color = area.get_color();
+
+            if colors.contains(&color) {
+                problems.push(LayoutProblem::ColorCollision { color });
+            } else {
+                colors.push(color);
+            }
+
+            let mut tiles = Vec::new();
+            for &c in area.get_sections() {
+                match owners.get_mut(c) {
+                    Some(owner) => {
+                        if !tiles.contains(&c) {
+                            owner.push(color);
+                            tiles.push(c);
+                        }
+                    }
+                    None => problems.push(LayoutProblem::OutOfBounds { c }),
+                }
+            }
+
+            match tiles.first() {
+                None => problems.push(LayoutProblem::EmptyArea { color }),
+                Some(&first) => {
+                    if connected(&tiles, first, n).len() != tiles.len() {
+                        problems.push(LayoutProblem::NonContiguous { color });
+                    }
+                }
+            }
+        }
+
+        for (c, owner) in owners.into_iter().enumerate() {
+            let tile = Coord {
+                row: c / n,
+                col: c % n,
+            };
+
+            match owner.len() {
+                0 => problems.push(LayoutProblem::Uncovered { tile }),
+                1 => {}
+                _ => problems.push(LayoutProblem::Overlap {
+                    tile,
+                    colors: owner,
+                }),
             }
         }
 
-        Err(QueensError::AreaNotFound { c: index })
+        problems
+    }
+
+    // The area of a tile, the first one where invalid layouts overlap. Checking many tiles is
+    // quicker on the area indices of a Board.
+    pub fn get_area_at(&self, coord: Coord) -> QueensResult<&Area> {
+        let c = Coord::new(coord.row, coord.col, self.n)?.get_index(self.n);
+        self.areas
+            .iter()
+            .find(|area| area.sections.contains(&c))
+            .ok_or(QueensError::AreaNotFound { c })
     }
 
     // Easily solvable layout
-    pub fn easy_layout(n: usize) -> Layout {
+    pub fn easy_layout(n: usize) -> QueensResult<Layout> {
+        LayoutType::Easy.check_size(n)?;
         let mut res: Vec<Vec<Section>> = vec![];
 
         for i in 0..n {
@@ -123,127 +250,146 @@ impl Layout {
         Layout::from_sections(res, n)
     }
 
-    // Complex layout
-    pub fn complex_layout(n: usize) -> Layout {
-        if n != 10 {
-            panic!("n needs to be 10 for complex layout")
+    // Hand-picked layout of size n
+    pub fn complex_layout(n: usize) -> QueensResult<Layout> {
+        LayoutType::Complex.check_size(n)?;
+
+        match COMPLEX_LAYOUTS
+            .split("\n\n")
+            .find(|template| template.trim().lines().count() == n)
+        {
+            Some(template) => template.parse(),
+            None => Err(LayoutType::Complex.size_error(n)),
         }
-        let r1 = vec![
-            section(0..n),
-            section(n * 2 - 1),
-            section(n * 3 - 1),
-            section(n * 4 - 1),
-            section(n * 5 - 1),
-            section(n * 6 - 1),
-            section(n * 7 - 1),
-            section(n * 8 - 1),
-            section(n * 9 - 1),
-            section(n * n - 1),
-        ];
-
-        let r2 = vec![
-            section(n..=n + 2),
-            section(n * 2..=n * 2 + 2),
-            section(n * 3..=n * 3 + 4),
-        ];
-
-        let r3 = vec![
-            section(n * 3 - 3),
-            section(n * 4 - 3),
-            section(n * 5 - 3..n * 5 - 1),
-            section(n * 6 - 6..n * 6 - 1),
-            section(n * 7 - 5..n * 7 - 1),
-            section(n * 8 - 2),
-        ];
-
-        let r4 = vec![
-            section(n + 3..2 * n - 1),
-            section(2 * n + 3..3 * n - 3),
-            section(3 * n - 2),
-            section(4 * n - 5),
-            section(4 * n - 2),
-        ];
-
-        let r5 = vec![section(n * 4 + 3..=n * 4 + 4)];
-
-        let r6 = vec![section(n * 4 - 4), section(n * 4 + 5..=n * 4 + 6)];
-
-        let r7 = vec![
-            section(n * 4..n * 4 + 3),
-            section(n * 5..n * 5 + 4),
-            section(n * 6..n * 6 + 4),
-            section(n * 7..n * 7 + 2),
-            section(n * 8..n * 8 + 5),
-            section(n * 9..n * 9 + 5),
-        ];
-
-        let r8 = vec![section(n * 7 + 2..=n * 7 + 3)];
-
-        let r9 = vec![
-            section(6 * n + 4),
-            section(7 * n + 4..7 * n + 8),
-            section(8 * n + 5..8 * n + 9),
-            section(9 * n + 5..9 * n + 8),
-        ];
-
-        let r10 = vec![section(n * n - 2)];
-
-        Layout::from_sections(vec![r1, r2, r3, r4, r5, r6, r7, r8, r9, r10], n)
-    }
-
-    // Generate a solvable layout
-    pub fn generate_layout(n: usize) -> Layout {
-        let size: usize = n.pow(2);
-        let mut areas: Vec<Vec<Section>> = Vec::new();
-        let mut numbers: Vec<usize> = (0..size).collect();
-        let mut rng = rand::rng();
-        numbers.shuffle(&mut rng);
-        let mut unavailable = vec![false; size];
-        let mut placed = vec![false; size];
+    }
 
-        for _ in 0..n {
-            let mut vec: Vec<Section> = Vec::new();
-            let mut r = numbers.pop().unwrap();
+    // Generate a solvable layout.
+    // Generation only depends on n and the seed, so a seed identifies a puzzle on every machine.
+    pub fn generate_layout(n: usize, seed: u64) -> QueensResult<Layout> {
+        LayoutType::Generated.check_size(n)?;
+        let (areas, _) = Self::grow_areas(n, &mut ChaCha8Rng::seed_from_u64(seed));
 
-            while unavailable[r] {
-                match numbers.pop() {
-                    None => {
-                        // try again
-                        return Layout::generate_layout(n);
-                    }
-                    Some(num) => {
-                        r = num;
+        Layout::from_sections(areas, n)
+    }
+
+    // Generate a layout with exactly one solution, like the hand-made templates.
+    pub fn generate_unique_layout(n: usize, seed: u64) -> QueensResult<Layout> {
+        Self::generate_unique_layout_with(n, seed, &mut || true)
+    }
+
+    // Like generate_unique_layout, calling keep_going before each round of new areas.
+    // Stops with GenerationCancelled once keep_going gives false.
+    pub fn generate_unique_layout_with(
+        n: usize,
+        seed: u64,
+        keep_going: &mut dyn FnMut() -> bool,
+    ) -> QueensResult<Layout> {
+        LayoutType::GeneratedUnique.check_size(n)?;
+        Self::unique_layout(n, &mut ChaCha8Rng::seed_from_u64(seed), keep_going)
+    }
+
+    // Areas are grown randomly and then reshaped until the seeded queens are the only solution.
+    // Gives up with GenerationFailed after UNIQUE_ROUNDS rounds of new areas.
+    fn unique_layout<R: Rng>(
+        n: usize,
+        rng: &mut R,
+        keep_going: &mut dyn FnMut() -> bool,
+    ) -> QueensResult<Layout> {
+        for _ in 0..UNIQUE_ROUNDS {
+            if !keep_going() {
+                return Err(QueensError::GenerationCancelled);
+            }
+            let (mut areas, queens) = Self::grow_areas(n, rng);
+
+            for _ in 0..n.pow(2) {
+                let layout = Layout::from_sections(areas.clone(), n)?;
+                let solutions = match Solver::new(&layout, n) {
+                    Ok(solver) => solver.solutions(Some(2)),
+                    Err(_) => break,
+                };
+
+                // the seeded queens always solve the layout, so look for any other solution
+                let other = solutions
+                    .into_iter()
+                    .find(|s| s.iter().any(|c| !queens.contains(c)));
+
+                match other {
+                    None => return Ok(layout),
+                    Some(other) => {
+                        if !Self::reshape(&mut areas, &other, &queens, n, rng) {
+                            // stuck, so grow new areas instead
+                            break;
+                        }
                     }
                 }
             }
+        }
 
-            for i in 0..n {
-                unavailable[i * n + (r % n)] = true;
-                unavailable[r - (r % n) + i] = true;
-            }
+        Err(QueensError::GenerationFailed)
+    }
 
-            let bottom_row = r < n;
-            let top_row = r >= size - n;
-            let left_col = r % n == 0;
-            let right_col = r % n == n - 1;
+    // Generate a layout with a unique solution of the given difficulty.
+    // Hard and expert layouts are rare on small grids, so after enough attempts the closest
+    // difficulty found is used instead. Gives the difficulty the layout was rated, so a caller
+    // can tell the player when it is not the one asked for.
+    pub fn generate_rated_layout(
+        n: usize,
+        difficulty: Difficulty,
+        seed: u64,
+    ) -> QueensResult<(Layout, Difficulty)> {
+        Self::generate_rated_layout_with(n, difficulty, seed, &mut |_| true)
+    }
 
-            if !bottom_row && !left_col {
-                unavailable[r - n - 1] = true;
-            }
-            if !bottom_row && !right_col {
-                unavailable[r - n + 1] = true;
+    // Like generate_rated_layout, calling on_attempt with the number of layouts tried so far
+    // while generating. Stops with GenerationCancelled once on_attempt gives false.
+    pub fn generate_rated_layout_with(
+        n: usize,
+        difficulty: Difficulty,
+        seed: u64,
+        on_attempt: &mut dyn FnMut(usize) -> bool,
+    ) -> QueensResult<(Layout, Difficulty)> {
+        LayoutType::Rated(difficulty).check_size(n)?;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut closest: Option<(Layout, Difficulty)> = None;
+
+        for attempt in 0..rating_attempts(n) {
+            let layout = Layout::unique_layout(n, &mut rng, &mut || on_attempt(attempt))?;
+            let rated = match Rating::rate(&layout, n) {
+                Ok(rating) => rating.difficulty,
+                Err(_) => continue,
+            };
+
+            if rated == difficulty {
+                return Ok((layout, rated));
             }
-            if !top_row && !left_col {
-                unavailable[r + n - 1] = true;
-            }
-            if !top_row && !right_col {
-                unavailable[r + n + 1] = true;
+
+            let distance = |d: Difficulty| (d as usize).abs_diff(difficulty as usize);
+            if closest
+                .as_ref()
+                .is_none_or(|(_, d)| distance(rated) < distance(*d))
+            {
+                closest = Some((layout, rated));
             }
+        }
 
-            placed[r] = true;
+        closest.ok_or(QueensError::GenerationFailed)
+    }
 
-            vec.push(section(r));
-            areas.push(vec);
+    // Grow n areas randomly around n seeded queens that do not attack each other.
+    // Gives the areas and the seeded queens. Needs n of at least MIN_N, as smaller grids have no
+    // such queens.
+    fn grow_areas<R: Rng>(n: usize, rng: &mut R) -> (Vec<Vec<Section>>, Vec<usize>) {
+        let size: usize = n.pow(2);
+        // seeding can run out of free tiles, so try again until all n queens fit
+        let queens = loop {
+            if let Some(queens) = Self::seed_queens(n, rng) {
+                break queens;
+            }
+        };
+        let mut areas: Vec<Vec<Section>> = queens.iter().map(|&r| vec![section(r)]).collect();
+        let mut placed = vec![false; size];
+        for &r in &queens {
+            placed[r] = true;
         }
 
         let mut number_placed = n;
@@ -286,24 +432,236 @@ impl Layout {
             }
         }
 
-        Layout::from_sections(areas, n)
+        (areas, queens)
+    }
+
+    // Place n queens that do not attack each other on random tiles, none if they do not fit
+    fn seed_queens<R: Rng>(n: usize, rng: &mut R) -> Option<Vec<usize>> {
+        let size: usize = n.pow(2);
+        let mut queens: Vec<usize> = Vec::new();
+        let mut numbers: Vec<usize> = (0..size).collect();
+        numbers.shuffle(rng);
+        let mut unavailable = vec![false; size];
+
+        for _ in 0..n {
+            let mut r = numbers.pop()?;
+            while unavailable[r] {
+                r = numbers.pop()?;
+            }
+
+            for i in 0..n {
+                unavailable[i * n + (r % n)] = true;
+                unavailable[r - (r % n) + i] = true;
+            }
+
+            let bottom_row = r < n;
+            let top_row = r >= size - n;
+            let left_col = r.is_multiple_of(n);
+            let right_col = r % n == n - 1;
+
+            if !bottom_row && !left_col {
+                unavailable[r - n - 1] = true;
+            }
+            if !bottom_row && !right_col {
+                unavailable[r - n + 1] = true;
+            }
+            if !top_row && !left_col {
+                unavailable[r + n - 1] = true;
+            }
+            if !top_row && !right_col {
+                unavailable[r + n + 1] = true;
+            }
+
+            queens.push(r);
+        }
+
+        Some(queens)
+    }
+
+    // Move one tile of an unwanted solution into a neighbouring area, which rules that solution
+    // out. Tiles of the old area that would be cut off from its seeded queen move along, so every
+    // area stays contiguous and the seeded queens remain a solution. Gives false if no tile can
+    // be moved.
+    fn reshape<R: Rng>(
+        areas: &mut [Vec<Section>],
+        solution: &[usize],
+        queens: &[usize],
+        n: usize,
+        rng: &mut R,
+    ) -> bool {
+        let mut area_of = vec![0; n.pow(2)];
+        for (i, area) in areas.iter().enumerate() {
+            for section in area {
+                for c in section.inner.clone() {
+                    area_of[c] = i;
+                }
+            }
+        }
+
+        let mut candidates: Vec<usize> = solution
+            .iter()
+            .copied()
+            .filter(|c| !queens.contains(c))
+            .collect();
+        candidates.shuffle(rng);
+
+        for c in candidates {
+            let from = area_of[c];
+            let targets: Vec<usize> = neighbours(c, n)
+                .into_iter()
+                .map(|i| area_of[i])
+                .filter(|&a| a != from)
+                .collect();
+
+            if let Some(&to) = targets.choose(rng) {
+                let remaining: Vec<usize> = (0..n.pow(2))
+                    .filter(|&i| i != c && area_of[i] == from)
+                    .collect();
+                let kept = connected(&remaining, queens[from], n);
+
+                for i in (0..n.pow(2)).filter(|i| area_of[*i] == from && !kept.contains(i)) {
+                    areas[to].push(section(i));
+                }
+                areas[from] = kept.into_iter().map(section).collect();
+
+                return true;
+            }
+        }
+
+        false
     }
 }
 
-fn section_to_int(args: Vec<Section>) -> Vec<usize> {
-    let mut vec = Vec::new();
+// Orthogonal neighbours of a tile
+fn neighbours(c: usize, n: usize) -> Vec<usize> {
+    match Coord::from_index(c, n) {
+        Ok(coord) => coord
+            .orthogonal_neighbours(n)
+            .map(|i| i.get_index(n))
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// The tiles orthogonally connected to start
+pub fn connected(tiles: &[usize], start: usize, n: usize) -> Vec<usize> {
+    let mut seen = vec![start];
+    let mut queue = vec![start];
 
-    for section in args {
-        vec.extend(section.inner.clone());
+    while let Some(c) = queue.pop() {
+        for i in neighbours(c, n) {
+            if tiles.contains(&i) && !seen.contains(&i) {
+                seen.push(i);
+                queue.push(i);
+            }
+        }
     }
 
-    vec
+    seen
 }
 
-#[derive(Default, Clone, PartialEq)]
+// Layouts are equal when they color every tile the same, in whatever order the areas list them
+impl PartialEq for Layout {
+    fn eq(&self, other: &Layout) -> bool {
+        self.n == other.n && self.tile_colors() == other.tile_colors()
+    }
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum LayoutType {
     Easy,
     Complex,
     #[default]
     Generated,
+    GeneratedUnique,
+    Rated(Difficulty),
+}
+
+impl LayoutType {
+    // Grid sizes the layout type can make
+    pub fn get_sizes(&self) -> RangeInclusive<usize> {
+        MIN_N..=MAX_N
+    }
+
+    pub fn check_size(&self, n: usize) -> QueensResult<()> {
+        if self.get_sizes().contains(&n) {
+            Ok(())
+        } else {
+            Err(self.size_error(n))
+        }
+    }
+
+    fn size_error(&self, n: usize) -> QueensError {
+        QueensError::UnsupportedSize {
+            layout_type: self.clone(),
+            n,
+        }
+    }
+}
+
+impl Display for LayoutType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutType::Easy => write!(f, "Easy layout"),
+            LayoutType::Complex => write!(f, "Complex template"),
+            LayoutType::Generated => write!(f, "Generated"),
+            LayoutType::GeneratedUnique => write!(f, "Unique"),
+            LayoutType::Rated(difficulty) => write!(f, "{}", difficulty),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn larger_grids_try_fewer_rated_layouts() {
+        assert_eq!(rating_attempts(MIN_N), RATING_ATTEMPTS);
+        assert_eq!(rating_attempts(FULL_RATING_N), RATING_ATTEMPTS);
+        assert_eq!(rating_attempts(FULL_RATING_N + 1), RATING_ATTEMPTS / 2);
+        assert_eq!(rating_attempts(MAX_N), RATING_ATTEMPTS / 16);
+    }
+
+    #[test]
+    fn complex_templates_cover_every_size() {
+        for n in LayoutType::Complex.get_sizes() {
+            let layout = Layout::complex_layout(n).unwrap();
+            assert_eq!(layout.get_n(), n);
+            assert!(layout.validate().is_empty());
+            assert!(Solver::new(&layout, n).unwrap().count_solutions(1) > 0);
+        }
+        assert!(Layout::complex_layout(MAX_N + 1).is_err());
+    }
+
+    #[test]
+    fn rated_layouts_support_every_size() {
+        let expert = LayoutType::Rated(Difficulty::Expert);
+
+        assert_eq!(expert.get_sizes(), MIN_N..=MAX_N);
+        assert!(expert.check_size(MAX_N).is_ok());
+        assert!(matches!(
+            Layout::generate_rated_layout(MAX_N + 1, Difficulty::Easy, 0),
+            Err(QueensError::UnsupportedSize { n: 17, .. })
+        ));
+    }
+
+    #[test]
+    fn rated_layouts_give_the_difficulty_found() {
+        // Expert layouts do not fit on the smallest grid
+        let (layout, rated) = Layout::generate_rated_layout(MIN_N, Difficulty::Expert, 0).unwrap();
+        assert_ne!(rated, Difficulty::Expert);
+        assert_eq!(Rating::rate(&layout, MIN_N).unwrap().difficulty, rated);
+    }
+
+    #[test]
+    fn looks_up_the_area_of_a_tile() {
+        let layout: Layout = "AAAB\nCAAB\nCDDB\nCDDD".parse().unwrap();
+        let area = |row, col| layout.get_area_at(Coord { row, col }).unwrap();
+
+        assert_eq!(area(0, 3), area(2, 3));
+        assert_ne!(area(0, 3), area(0, 0));
+        assert_eq!(area(1, 1).get_sections(), &vec![0, 1, 2, 5, 6]);
+        assert!(layout.get_area_at(Coord { row: 4, col: 0 }).is_err());
+    }
 }
@@ -0,0 +1,132 @@
+use crate::model::coord::Coord;
+use std::fmt::{Display, Formatter};
+
+// Reason a layout cannot be played
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutProblem {
+    Uncovered { tile: Coord },
+    Overlap { tile: Coord, colors: Vec<u8> },
+    OutOfBounds { c: usize },
+    EmptyArea { color: u8 },
+    NonContiguous { color: u8 },
+    WrongAreaCount { expected: usize, found: usize },
+    ColorCollision { color: u8 },
+}
+
+impl Display for LayoutProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutProblem::Uncovered { tile } => {
+                write!(f, "Tile {} is not in any area", tile)
+            }
+            LayoutProblem::Overlap { tile, colors } => {
+                write!(f, "Tile {} is in areas {:?}", tile, colors)
+            }
+            LayoutProblem::OutOfBounds { c } => {
+                write!(f, "Coordinate {} is outside the grid", c)
+            }
+            LayoutProblem::EmptyArea { color } => write!(f, "Area {} has no tiles", color),
+            LayoutProblem::NonContiguous { color } => {
+                write!(f, "Area {} is split into separate parts", color)
+            }
+            LayoutProblem::WrongAreaCount { expected, found } => {
+                write!(f, "Found {} areas, expected {}", found, expected)
+            }
+            LayoutProblem::ColorCollision { color } => {
+                write!(f, "Several areas have color {}", color)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::layout::{section, Area, Layout};
+
+    fn area(tiles: &[usize], color: u8) -> Area {
+        Area::from_sections(tiles.iter().map(|&c| section(c)).collect(), color)
+    }
+
+    // 3 by 3 grid with one area per row, with the given row replaced
+    fn rows_with(row: usize, replacement: Vec<Area>) -> Layout {
+        let mut areas: Vec<Area> = (0..3)
+            .map(|r| area(&[3 * r, 3 * r + 1, 3 * r + 2], r as u8))
+            .collect();
+        areas.splice(row..row + 1, replacement);
+        Layout::new(areas, 3)
+    }
+
+    #[test]
+    fn valid() {
+        assert!(rows_with(0, vec![area(&[0, 1, 2], 0)])
+            .validate()
+            .is_empty());
+        assert!(Layout::complex_layout(10).unwrap().validate().is_empty());
+    }
+
+    #[test]
+    fn non_contiguous() {
+        let layout = rows_with(1, vec![area(&[3, 5, 1], 1)]);
+        let problems = layout.validate();
+
+        assert!(problems.contains(&LayoutProblem::NonContiguous { color: 1 }));
+    }
+
+    #[test]
+    fn uncovered() {
+        let layout = rows_with(2, vec![area(&[6, 7], 2)]);
+
+        assert_eq!(
+            layout.validate(),
+            vec![LayoutProblem::Uncovered {
+                tile: Coord { row: 2, col: 2 }
+            }]
+        );
+    }
+
+    #[test]
+    fn overlap() {
+        let layout = rows_with(1, vec![area(&[3, 4, 5, 6], 1)]);
+
+        assert_eq!(
+            layout.validate(),
+            vec![LayoutProblem::Overlap {
+                tile: Coord { row: 2, col: 0 },
+                colors: vec![1, 2]
+            }]
+        );
+    }
+
+    #[test]
+    fn wrong_area_count() {
+        let layout = rows_with(2, vec![area(&[6], 2), area(&[7], 3), area(&[8], 4)]);
+
+        assert_eq!(
+            layout.validate(),
+            vec![LayoutProblem::WrongAreaCount {
+                expected: 3,
+                found: 5
+            }]
+        );
+    }
+
+    #[test]
+    fn empty_area_and_out_of_bounds() {
+        let layout = rows_with(2, vec![area(&[6, 7, 8, 9], 2), area(&[], 3)]);
+        let problems = layout.validate();
+
+        assert!(problems.contains(&LayoutProblem::OutOfBounds { c: 9 }));
+        assert!(problems.contains(&LayoutProblem::EmptyArea { color: 3 }));
+    }
+
+    #[test]
+    fn color_collision() {
+        let layout = rows_with(2, vec![area(&[6, 7, 8], 1)]);
+
+        assert_eq!(
+            layout.validate(),
+            vec![LayoutProblem::ColorCollision { color: 1 }]
+        );
+    }
+}
@@ -1,5 +1,20 @@
+pub mod board;
+pub mod coord;
+pub mod daily;
+pub mod deduction;
+pub mod difficulty;
+pub mod editor;
+pub mod format;
 pub mod game_rule_broken;
+pub mod generation;
 pub mod grid;
+pub mod hint;
+pub mod history;
 pub mod layout;
+pub mod layout_problem;
+pub mod pack;
+pub mod save;
+pub mod solver;
 pub mod state;
+pub mod stats;
 pub mod tile;
@@ -0,0 +1,363 @@
+// Puzzle packs: named layouts curated by hand, listed in the level select.
+//
+// A pack is a JSON file like this, where each layout is in the text format:
+//
+//     {
+//       "name": "Classics",
+//       "author": "Queens",
+//       "levels": [
+//         {
+//           "name": "First steps",
+//           "author": "Someone else",
+//           "difficulty": "Easy",
+//           "layout": ["AAAB", "CAAB", "CDDB", "CDDD"],
+//           "solution": [[0, 1], [1, 3], [2, 0], [3, 2]]
+//         }
+//       ]
+//     }
+//
+// Authors, difficulties and solutions are optional. A given solution is checked on loading.
+
+use crate::errors::{QueensError, QueensResult};
+use crate::model::board::{MAX_N, MIN_N};
+use crate::model::coord::Coord;
+use crate::model::difficulty::Difficulty;
+use crate::model::game_rule_broken::check_rules;
+use crate::model::grid::Grid;
+use crate::model::layout::Layout;
+use crate::model::save;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+// Pack that comes with the game
+const BUILT_IN: &str = include_str!("../../packs/classic.json");
+
+// Folder in the data directory with the packs of the player
+const PACK_DIR: &str = "packs";
+
+const LEVEL_FILE: &str = "levels.json";
+
+#[derive(Deserialize)]
+struct PackFile {
+    name: String,
+    #[serde(default)]
+    author: Option<String>,
+    levels: Vec<LevelFile>,
+}
+
+#[derive(Deserialize)]
+struct LevelFile {
+    name: String,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    difficulty: Option<Difficulty>,
+    layout: Vec<String>,
+    // (row, col) of each queen
+    #[serde(default)]
+    solution: Option<Vec<(usize, usize)>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Level {
+    pub name: String,
+    pub author: Option<String>,
+    pub difficulty: Option<Difficulty>,
+    pub layout: Layout,
+    pub solution: Option<Vec<Coord>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Pack {
+    pub name: String,
+    pub author: Option<String>,
+    pub levels: Vec<Level>,
+}
+
+impl Pack {
+    pub fn parse(json: &str) -> QueensResult<Pack> {
+        let file: PackFile = serde_json::from_str(json).map_err(|e| QueensError::LoadFailed {
+            reason: e.to_string(),
+        })?;
+
+        let invalid = |reason: String| QueensError::InvalidPack {
+            pack: file.name.clone(),
+            reason,
+        };
+
+        let mut levels: Vec<Level> = Vec::new();
+        for level in file.levels {
+            if levels.iter().any(|l| l.name == level.name) {
+                return Err(invalid(format!("{} is there twice", level.name)));
+            }
+
+            let layout = parse_layout(&level.layout)
+                .map_err(|e| invalid(format!("{}: {}", level.name, e)))?;
+            let solution = level
+                .solution
+                .map(|queens| {
+                    let coords = queens
+                        .into_iter()
+                        .map(|(row, col)| Coord::new(row, col, layout.get_n()))
+                        .collect::<QueensResult<Vec<Coord>>>()?;
+                    check_solution(&layout, &coords)?;
+                    Ok(coords)
+                })
+                .transpose()
+                .map_err(|e: QueensError| invalid(format!("{}: {}", level.name, e)))?;
+
+            levels.push(Level {
+                name: level.name,
+                author: level.author.or(file.author.clone()),
+                difficulty: level.difficulty,
+                layout,
+                solution,
+            });
+        }
+
+        Ok(Pack {
+            name: file.name,
+            author: file.author,
+            levels,
+        })
+    }
+
+    pub fn built_in() -> QueensResult<Pack> {
+        Pack::parse(BUILT_IN)
+    }
+
+    pub fn load_file(path: &Path) -> QueensResult<Pack> {
+        let failed = |reason: &dyn Display| QueensError::LoadFailed {
+            reason: format!("{}: {}", path.display(), reason),
+        };
+
+        let json = fs::read_to_string(path).map_err(|e| failed(&e))?;
+        Pack::parse(&json).map_err(|e| match e {
+            QueensError::LoadFailed { reason } => failed(&reason),
+            e => e,
+        })
+    }
+
+    // The pack in a file, or the packs in the JSON files of a directory
+    pub fn load_path(path: &Path) -> Vec<QueensResult<Pack>> {
+        if !path.is_dir() {
+            return vec![Pack::load_file(path)];
+        }
+
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                return vec![Err(QueensError::LoadFailed {
+                    reason: format!("{}: {}", path.display(), e),
+                })]
+            }
+        };
+
+        let mut files: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .collect();
+        files.sort();
+
+        reject_duplicates(files.iter().map(|file| Pack::load_file(file)).collect())
+    }
+
+    // The built in pack, then the ones in the packs folder of the data directory
+    pub fn load_all() -> Vec<QueensResult<Pack>> {
+        let mut packs = vec![Pack::built_in()];
+
+        match save::get_path(PACK_DIR) {
+            Ok(dir) if dir.is_dir() => packs.extend(Pack::load_path(&dir)),
+            Ok(_) => {}
+            Err(e) => packs.push(Err(e)),
+        }
+
+        reject_duplicates(packs)
+    }
+
+    pub fn get_level(&self, name: &str) -> Option<&Level> {
+        self.levels.iter().find(|l| l.name == name)
+    }
+}
+
+// Levels are known by the name of their pack, so a pack named like an earlier one is left out
+fn reject_duplicates(packs: Vec<QueensResult<Pack>>) -> Vec<QueensResult<Pack>> {
+    let mut names: Vec<String> = Vec::new();
+
+    packs
+        .into_iter()
+        .map(|pack| {
+            let pack = pack?;
+            if names.contains(&pack.name) {
+                return Err(QueensError::InvalidPack {
+                    pack: pack.name,
+                    reason: "another pack has the same name".to_string(),
+                });
+            }
+            names.push(pack.name.clone());
+            Ok(pack)
+        })
+        .collect()
+}
+
+// The layout of a level, which must be playable
+fn parse_layout(rows: &[String]) -> QueensResult<Layout> {
+    let layout: Layout = rows.join("\n").parse()?;
+    let n = layout.get_n();
+    if n < MIN_N {
+        return Err(QueensError::GridTooSmall { n, min: MIN_N });
+    }
+    if n > MAX_N {
+        return Err(QueensError::GridTooLarge { n, max: MAX_N });
+    }
+
+    let problems = layout.validate();
+    if !problems.is_empty() {
+        return Err(QueensError::InvalidLayout { problems });
+    }
+
+    Ok(layout)
+}
+
+// Fails unless the queens solve the layout
+fn check_solution(layout: &Layout, queens: &[Coord]) -> QueensResult<()> {
+    let mut grid = Grid::from_layout(layout);
+    for &coord in queens {
+        grid.place_queen(coord)?;
+    }
+
+    let (broken, won) = check_rules(layout, &grid)?;
+    match broken.first() {
+        Some(rule) => Err(QueensError::WrongSolution {
+            reason: rule.to_string(),
+        }),
+        None if !won => Err(QueensError::WrongSolution {
+            reason: "not every row has a queen".to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+// A level of a pack, by name
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelId {
+    pub pack: String,
+    pub level: String,
+}
+
+// Levels solved on this machine
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LevelLog {
+    completed: Vec<LevelId>,
+}
+
+impl LevelLog {
+    pub fn load() -> QueensResult<LevelLog> {
+        Ok(save::read_json(LEVEL_FILE)?.unwrap_or_default())
+    }
+
+    pub fn store(&self) -> QueensResult<()> {
+        save::write_json(LEVEL_FILE, self)
+    }
+
+    pub fn record(&mut self, id: LevelId) {
+        if !self.is_completed(&id) {
+            self.completed.push(id);
+        }
+    }
+
+    pub fn is_completed(&self, id: &LevelId) -> bool {
+        self.completed.contains(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::difficulty::Rating;
+    use crate::model::solver::Solver;
+
+    fn pack(layout: &str, solution: &str) -> String {
+        format!(
+            r#"{{"name": "Test", "levels": [{{"name": "One", "layout": {}, "solution": {}}}]}}"#,
+            layout, solution
+        )
+    }
+
+    #[test]
+    fn built_in_pack_loads() {
+        let pack = Pack::built_in().unwrap();
+
+        assert_eq!(pack.name, "Classics");
+        assert!(!pack.levels.is_empty());
+    }
+
+    #[test]
+    fn built_in_levels_have_one_solution() {
+        for level in Pack::built_in().unwrap().levels {
+            let n = level.layout.get_n();
+            let solver = Solver::new(&level.layout, n).unwrap();
+            assert_eq!(solver.count_solutions(2), 1, "{}", level.name);
+
+            let rating = Rating::rate(&level.layout, n).unwrap();
+            assert_eq!(level.difficulty, Some(rating.difficulty), "{}", level.name);
+            assert!(level.solution.is_some(), "{}", level.name);
+        }
+    }
+
+    #[test]
+    fn checks_the_solution() {
+        let layout = r#"["AAAB", "CAAB", "CDDB", "CDDD"]"#;
+
+        assert!(Pack::parse(&pack(layout, "[[0, 1], [1, 3], [2, 0], [3, 2]]")).is_ok());
+        assert!(matches!(
+            Pack::parse(&pack(layout, "[[0, 0], [1, 3], [2, 1], [3, 2]]")),
+            Err(QueensError::InvalidPack { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_unplayable_layouts() {
+        // Area A is split in two
+        let split = pack(r#"["ABBA", "CCCC", "DDDD", "DDDD"]"#, "null");
+        let small = pack(r#"["AAB", "ABB", "CCC"]"#, "null");
+
+        for json in [split, small] {
+            assert!(matches!(
+                Pack::parse(&json),
+                Err(QueensError::InvalidPack { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_packs_of_the_same_name() {
+        let one = pack(r#"["AAAB", "CAAB", "CDDB", "CDDD"]"#, "null");
+        let packs = reject_duplicates(vec![
+            Pack::built_in(),
+            Pack::parse(&one),
+            Pack::parse(&one),
+            Pack::built_in(),
+        ]);
+
+        assert!(packs[0].is_ok() && packs[1].is_ok());
+        for res in &packs[2..] {
+            assert!(matches!(res, Err(QueensError::InvalidPack { .. })));
+        }
+    }
+
+    #[test]
+    fn rejects_levels_of_the_same_name() {
+        let json = r#"{"name": "Test", "levels": [
+            {"name": "One", "layout": ["AAAB", "CAAB", "CDDB", "CDDD"]},
+            {"name": "One", "layout": ["AAAB", "CAAB", "CDDB", "CDDD"]}
+        ]}"#;
+
+        assert!(matches!(
+            Pack::parse(json),
+            Err(QueensError::InvalidPack { .. })
+        ));
+    }
+}
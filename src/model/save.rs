@@ -0,0 +1,123 @@
+use crate::errors::{QueensError, QueensResult};
+use crate::model::state::State;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+// Bump when the saved state changes shape, older saves are then refused
+const SAVE_VERSION: u32 = 1;
+
+const SAVE_FILE: &str = "save.json";
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    state: State,
+}
+
+// Only the version, so saves of other versions can be told apart
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+// File in the data directory of the game
+pub fn get_path(name: &str) -> QueensResult<PathBuf> {
+    match dirs::data_dir() {
+        Some(dir) => Ok(dir.join("queens").join(name)),
+        None => Err(QueensError::SaveFailed {
+            reason: "no data directory".to_string(),
+        }),
+    }
+}
+
+fn read(name: &str) -> QueensResult<Option<String>> {
+    match fs::read_to_string(get_path(name)?) {
+        Ok(json) => Ok(Some(json)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(QueensError::LoadFailed {
+            reason: e.to_string(),
+        }),
+    }
+}
+
+fn parse<T: DeserializeOwned>(json: &str) -> QueensResult<T> {
+    serde_json::from_str(json).map_err(|e| QueensError::LoadFailed {
+        reason: e.to_string(),
+    })
+}
+
+// Write text to a file in the data directory
+pub fn write_text(name: &str, text: &str) -> QueensResult<()> {
+    let path = get_path(name)?;
+    let failed = |e: &dyn ToString| QueensError::SaveFailed {
+        reason: e.to_string(),
+    };
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| failed(&e))?;
+    }
+    fs::write(path, text).map_err(|e| failed(&e))
+}
+
+// Write a value to a JSON file in the data directory
+pub fn write_json<T: Serialize>(name: &str, value: &T) -> QueensResult<()> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| QueensError::SaveFailed {
+        reason: e.to_string(),
+    })?;
+    write_text(name, &json)
+}
+
+// Read a value from a JSON file in the data directory, none if there is no such file
+pub fn read_json<T: DeserializeOwned>(name: &str) -> QueensResult<Option<T>> {
+    match read(name)? {
+        Some(json) => parse(&json).map(Some),
+        None => Ok(None),
+    }
+}
+
+pub fn exists() -> bool {
+    get_path(SAVE_FILE).is_ok_and(|p| p.exists())
+}
+
+pub fn store(state: &State) -> QueensResult<()> {
+    write_json(
+        SAVE_FILE,
+        &SaveFile {
+            version: SAVE_VERSION,
+            state: state.clone(),
+        },
+    )
+}
+
+// The saved state, if there is one
+pub fn load() -> QueensResult<Option<State>> {
+    let Some(json) = read(SAVE_FILE)? else {
+        return Ok(None);
+    };
+
+    let header: SaveHeader = parse(&json)?;
+    if header.version != SAVE_VERSION {
+        return Err(QueensError::UnsupportedSaveVersion {
+            found: header.version,
+            expected: SAVE_VERSION,
+        });
+    }
+
+    let save: SaveFile = parse(&json)?;
+    save.state.check()?;
+
+    Ok(Some(save.state))
+}
+
+pub fn remove() -> QueensResult<()> {
+    match fs::remove_file(get_path(SAVE_FILE)?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(QueensError::SaveFailed {
+            reason: e.to_string(),
+        }),
+    }
+}
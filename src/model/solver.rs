@@ -0,0 +1,277 @@
+use crate::errors::{QueensError, QueensResult};
+use crate::model::board::{Bitboard, Board};
+use crate::model::layout::Layout;
+
+// Queen placement given as one tile index per row, ordered by row.
+pub type Solution = Vec<usize>;
+
+// Backtracking solver enumerating every placement of n queens with exactly one queen in each row,
+// column and area, and no two queens touching.
+// It always branches on the row, column or area with the fewest tiles left, which keeps the
+// search small even for big grids. The tiles still free are kept as a bitboard.
+#[derive(Clone, Debug)]
+pub struct Solver {
+    size: usize,
+    // Rows, columns and areas, each of which needs exactly one queen.
+    units: Vec<Bitboard>,
+    // The row, column and area of each tile, as indices into units.
+    units_of: Vec<[usize; 3]>,
+    // Tiles ruled out by a queen on each tile, including the tile itself.
+    attacks: Vec<Bitboard>,
+}
+
+impl Solver {
+    pub fn new(layout: &Layout, n: usize) -> QueensResult<Solver> {
+        let board = Board::new(layout)?;
+        if board.get_n() != n {
+            return Err(QueensError::LayoutSizeMismatch {
+                layout: board.get_n(),
+                n,
+            });
+        }
+        let size = n.pow(2);
+
+        let units: Vec<Bitboard> = board
+            .get_rows()
+            .iter()
+            .chain(board.get_cols().iter())
+            .chain(board.get_areas().iter())
+            .copied()
+            .collect();
+        let units_of: Vec<[usize; 3]> = (0..size)
+            .map(|c| [c / n, n + c % n, 2 * n + board.get_area_of(c)])
+            .collect();
+
+        let attacks = (0..size)
+            .map(|c| {
+                units_of[c]
+                    .iter()
+                    .fold(board.get_neighbours(c), |res, &u| res | units[u])
+            })
+            .collect();
+
+        Ok(Self {
+            size,
+            units,
+            units_of,
+            attacks,
+        })
+    }
+
+    // All solutions, stopping once `limit` of them have been found.
+    pub fn solutions(&self, limit: Option<usize>) -> Vec<Solution> {
+        self.solutions_with(&[], &[], limit)
+    }
+
+    // All solutions that include the given queens and avoid the excluded tiles, stopping once
+    // `limit` of them have been found.
+    pub fn solutions_with(
+        &self,
+        queens: &[usize],
+        excluded: &[usize],
+        limit: Option<usize>,
+    ) -> Vec<Solution> {
+        let mut res = Vec::new();
+
+        if limit != Some(0) {
+            self.search(queens, excluded, &mut |solution| {
+                res.push(solution);
+                limit.is_none_or(|l| res.len() < l)
+            });
+        }
+
+        res
+    }
+
+    // Number of solutions, counting no further than `limit`.
+    // A limit of 2 is enough to tell unsolvable, unique and ambiguous layouts apart.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let mut count = 0;
+
+        if limit > 0 {
+            self.search(&[], &[], &mut |_| {
+                count += 1;
+                count < limit
+            });
+        }
+
+        count
+    }
+
+    // Runs `on_solution` for each solution including the given queens and avoiding the excluded
+    // tiles until it returns false.
+    fn search(
+        &self,
+        queens: &[usize],
+        excluded: &[usize],
+        on_solution: &mut dyn FnMut(Solution) -> bool,
+    ) {
+        let mut free = Bitboard::full(self.size);
+        let mut done = vec![false; self.units.len()];
+
+        for &c in excluded {
+            if c < self.size {
+                free.remove(c);
+            }
+        }
+
+        for &c in queens {
+            if !free.contains(c) {
+                // out of bounds or attacked by another queen
+                return;
+            }
+            free = self.place(c, free, &mut done);
+        }
+
+        self.place_next(free, &mut done, &mut queens.to_vec(), on_solution);
+    }
+
+    // The tiles left free after placing a queen on c
+    fn place(&self, c: usize, free: Bitboard, done: &mut [bool]) -> Bitboard {
+        for u in self.units_of[c] {
+            done[u] = true;
+        }
+        free & !self.attacks[c]
+    }
+
+    fn remove(&self, c: usize, done: &mut [bool]) {
+        for u in self.units_of[c] {
+            done[u] = false;
+        }
+    }
+
+    // Places a queen in the most constrained unit, returns false if the search should stop.
+    fn place_next(
+        &self,
+        free: Bitboard,
+        done: &mut [bool],
+        queens: &mut Vec<usize>,
+        on_solution: &mut dyn FnMut(Solution) -> bool,
+    ) -> bool {
+        let mut best: Option<(Bitboard, usize)> = None;
+
+        for (u, tiles) in self.units.iter().enumerate() {
+            if done[u] {
+                continue;
+            }
+
+            let options = *tiles & free;
+            let count = options.count();
+            if best.is_none_or(|(_, f)| count < f) {
+                best = Some((options, count));
+            }
+        }
+
+        let Some((options, count)) = best else {
+            let mut solution = queens.clone();
+            solution.sort();
+            return on_solution(solution);
+        };
+
+        if count == 0 {
+            return true;
+        }
+
+        for c in options.iter() {
+            let next = self.place(c, free, done);
+            queens.push(c);
+
+            let keep_going = self.place_next(next, done, queens, on_solution);
+
+            queens.pop();
+            self.remove(c, done);
+
+            if !keep_going {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solver(layout: &Layout) -> Solver {
+        Solver::new(layout, layout.get_n()).unwrap()
+    }
+
+    #[test]
+    fn unique_layout_has_one_solution() {
+        for seed in 0..5 {
+            let layout = Layout::generate_unique_layout(8, seed).unwrap();
+            let solver = solver(&layout);
+
+            assert_eq!(solver.count_solutions(2), 1);
+            assert_eq!(solver.solutions(None).len(), 1);
+        }
+    }
+
+    #[test]
+    fn complex_layout_is_ambiguous() {
+        let solver = solver(&Layout::complex_layout(10).unwrap());
+
+        assert_eq!(solver.count_solutions(2), 2);
+        assert_eq!(solver.count_solutions(usize::MAX), 13);
+    }
+
+    #[test]
+    fn easy_layout_counts_every_placement() {
+        // Placements of n queens in distinct rows and columns where no two touch
+        for (n, count) in [(4, 2), (5, 14), (6, 90)] {
+            let layout = Layout::easy_layout(n).unwrap();
+            assert_eq!(solver(&layout).count_solutions(usize::MAX), count);
+        }
+    }
+
+    #[test]
+    fn no_solution() {
+        // A and B both need their queen in the first row
+        let layout: Layout = "AABB\nCCCC\nCCCC\nDDDD".parse().unwrap();
+        let solver = solver(&layout);
+
+        assert_eq!(solver.count_solutions(2), 0);
+        assert!(solver.solutions(None).is_empty());
+    }
+
+    #[test]
+    fn limits_are_honoured() {
+        let solver = solver(&Layout::easy_layout(6).unwrap());
+
+        assert_eq!(solver.count_solutions(0), 0);
+        assert!(solver.solutions(Some(0)).is_empty());
+        assert!(solver.solutions_with(&[0], &[], Some(0)).is_empty());
+        assert_eq!(solver.count_solutions(2), 2);
+        assert_eq!(solver.count_solutions(10), 10);
+        assert_eq!(solver.solutions(Some(5)).len(), 5);
+        assert_eq!(solver.solutions(Some(1)), solver.solutions(Some(5))[..1]);
+    }
+
+    #[test]
+    fn solutions_follow_the_rules() {
+        let n = 6;
+        let layout = Layout::easy_layout(n).unwrap();
+
+        for solution in solver(&layout).solutions(None) {
+            assert_eq!(solution.len(), n);
+            for (i, &c) in solution.iter().enumerate() {
+                assert_eq!(c / n, i);
+                for &t in &solution[i + 1..] {
+                    assert_ne!(c % n, t % n);
+                    assert!((c % n).abs_diff(t % n) > 1 || t / n - c / n > 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_size() {
+        let layout = Layout::easy_layout(5).unwrap();
+        assert!(matches!(
+            Solver::new(&layout, 6),
+            Err(QueensError::LayoutSizeMismatch { layout: 5, n: 6 })
+        ));
+    }
+}
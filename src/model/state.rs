@@ -1,44 +1,379 @@
 use crate::errors::{QueensError, QueensResult};
-use crate::model::game_rule_broken::GameRuleBroken;
+use crate::model::board::Board;
+use crate::model::coord::{Coord, Direction, Movement};
+use crate::model::daily::{Daily, DailyLog};
+use crate::model::deduction::Deducer;
+use crate::model::difficulty::{Difficulty, Rating};
+use crate::model::editor::Editor;
+use crate::model::game_rule_broken::{check_rules_on, GameRuleBroken};
+use crate::model::generation::Generation;
 use crate::model::grid::Grid;
+use crate::model::hint::Hint;
+use crate::model::history::{History, Move};
 use crate::model::layout::{Layout, LayoutType};
-use crate::model::tile::Tile;
+use crate::model::pack::{LevelId, LevelLog, Pack};
+use crate::model::save;
+use crate::model::solver::Solver;
+use crate::model::stats::{GameRecord, Statistics};
+use crate::model::tile::{Tile, TileState};
+use chrono::{Local, NaiveDate};
+use log::{debug, log_enabled, warn, Level};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct State {
     n: usize,
     layout_type: LayoutType,
+    auto_eliminate: bool,
     game_state: GameState,
+    // Seed for the next generated layout, none for a random one
+    #[serde(default)]
+    seed: Option<u64>,
+    // Game left for the main menu, which can be continued
+    #[serde(default)]
+    suspended: Option<Box<InGameState>>,
+    // Solved daily puzzles, loaded once needed. A file that failed to load is not read again.
+    #[serde(skip)]
+    daily_log: Option<QueensResult<DailyLog>>,
+    // Won games, loaded once needed
+    #[serde(skip)]
+    statistics: Option<QueensResult<Statistics>>,
+    // Puzzle packs and the levels solved, loaded once needed
+    #[serde(skip)]
+    packs: Option<Vec<QueensResult<Pack>>>,
+    #[serde(skip)]
+    level_log: Option<QueensResult<LevelLog>>,
+    // Game being generated in the background
+    #[serde(skip)]
+    generation: Option<Arc<Generation>>,
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub enum GameState {
     #[default]
     MainMenu,
-    InGame(InGameState),
-    Won,
+    InGame(Box<InGameState>),
+    // Waiting for the game to be generated
+    Generating,
+    Won(Summary),
+    Statistics,
+    Editor(Box<Editor>),
+    Levels,
+}
+
+// How a won game went
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Summary {
+    pub time: Duration,
+    pub moves: u32,
+    pub undos: u32,
+    pub hints: u32,
 }
 
 impl State {
     pub fn get_game_state(&self) -> &GameState {
         &self.game_state
     }
-    pub fn load_in_game(&mut self) {
-        self.game_state = GameState::InGame(InGameState::new(self.n, self.layout_type.clone()));
+    pub fn load_in_game(&mut self) -> QueensResult<()> {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let in_game = InGameState::new(self.n, self.layout_type.clone(), seed)?;
+
+        self.start(in_game);
+        Ok(())
+    }
+
+    // Generate a game like load_in_game, but on a background thread.
+    // The game starts once poll_generation finds it ready.
+    pub fn generate_in_game(&mut self) {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let generation = Generation::start(self.n, self.layout_type.clone(), seed);
+
+        self.generation = Some(Arc::new(generation));
+        self.game_state = GameState::Generating;
+    }
+
+    pub fn get_generation(&self) -> Option<&Generation> {
+        self.generation.as_deref()
+    }
+
+    // Start the generated game once it is ready. A failed generation goes back to the main menu.
+    pub fn poll_generation(&mut self) -> QueensResult<()> {
+        let Some(res) = self.generation.as_ref().and_then(|g| g.poll()) else {
+            return Ok(());
+        };
+
+        self.generation = None;
+        self.game_state = GameState::MainMenu;
+        self.start(res?);
+        Ok(())
+    }
+
+    pub fn cancel_generation(&mut self) {
+        self.generation = None;
+        self.game_state = GameState::MainMenu;
+    }
+
+    // Start the puzzle of today
+    pub fn load_daily(&mut self) -> QueensResult<()> {
+        let daily = Daily::today();
+        let mut in_game = InGameState::new(daily.n, LayoutType::GeneratedUnique, daily.seed)?;
+        in_game.daily = Some(daily.date);
+
+        self.n = daily.n;
+        self.start(in_game);
+        Ok(())
+    }
+
+    // Start a level of a puzzle pack
+    pub fn load_level(&mut self, id: LevelId) -> QueensResult<()> {
+        let level = self
+            .get_packs()
+            .iter()
+            .flatten()
+            .find(|pack| pack.name == id.pack)
+            .and_then(|pack| pack.get_level(&id.level))
+            .ok_or_else(|| QueensError::LevelNotFound {
+                pack: id.pack.clone(),
+                level: id.level.clone(),
+            })?;
+        let mut in_game = InGameState::from_layout(level.layout.clone())?;
+        in_game.difficulty = level.difficulty.or(in_game.difficulty);
+        in_game.level = Some(id);
+
+        self.n = in_game.layout.get_n();
+        self.start(in_game);
+        Ok(())
+    }
+
+    // Play a layout made elsewhere, like in the editor
+    pub fn load_layout(&mut self, layout: Layout) -> QueensResult<()> {
+        let in_game = InGameState::from_layout(layout)?;
+
+        self.n = in_game.layout.get_n();
+        self.start(in_game);
+        Ok(())
+    }
+
+    fn start(&mut self, mut in_game: InGameState) {
+        in_game.set_auto_eliminate(self.auto_eliminate);
+
+        self.game_state = GameState::InGame(Box::new(in_game));
+        self.suspended = None;
+    }
+
+    pub fn get_daily_log(&mut self) -> QueensResult<&mut DailyLog> {
+        self.daily_log
+            .get_or_insert_with(DailyLog::load)
+            .as_mut()
+            .map_err(|e| e.clone())
+    }
+
+    pub fn get_statistics(&mut self) -> QueensResult<&mut Statistics> {
+        self.statistics
+            .get_or_insert_with(Statistics::load)
+            .as_mut()
+            .map_err(|e| e.clone())
+    }
+
+    pub fn get_packs(&mut self) -> &Vec<QueensResult<Pack>> {
+        self.packs.get_or_insert_with(Pack::load_all)
+    }
+
+    pub fn get_level_log(&mut self) -> QueensResult<&mut LevelLog> {
+        self.level_log
+            .get_or_insert_with(LevelLog::load)
+            .as_mut()
+            .map_err(|e| e.clone())
+    }
+
+    // The packs and the levels solved together, for the level select
+    pub fn get_levels(&mut self) -> (&Vec<QueensResult<Pack>>, QueensResult<&LevelLog>) {
+        let packs = self.packs.get_or_insert_with(Pack::load_all);
+        let log = self
+            .level_log
+            .get_or_insert_with(LevelLog::load)
+            .as_ref()
+            .map_err(|e| e.clone());
+        (packs, log)
+    }
+
+    pub fn show_levels(&mut self) {
+        self.game_state = GameState::Levels;
+    }
+
+    pub fn show_statistics(&mut self) {
+        self.game_state = GameState::Statistics;
+    }
+
+    // Open the editor with a new layout of size n
+    pub fn show_editor(&mut self) {
+        self.game_state = GameState::Editor(Box::new(Editor::new(self.n)));
+    }
+
+    pub fn get_editor_mut(&mut self) -> QueensResult<&mut Editor> {
+        match &mut self.game_state {
+            GameState::Editor(editor) => Ok(editor),
+            _ => Err(QueensError::NotInEditor),
+        }
+    }
+
+    pub fn show_main_menu(&mut self) {
+        self.game_state = GameState::MainMenu;
+    }
+
+    pub fn set_game_won(&mut self) -> QueensResult<()> {
+        let in_game = self.in_game()?;
+        let summary = in_game.get_summary();
+        let daily = in_game.daily;
+        let level = in_game.level.clone();
+        let record = GameRecord {
+            date: Local::now().date_naive(),
+            n: in_game.layout.get_n(),
+            layout_type: in_game.layout_type.clone(),
+            difficulty: in_game.difficulty,
+            seed: in_game.seed,
+            daily: daily.is_some(),
+            summary,
+        };
+
+        // A log that cannot be loaded or saved should not keep the game from being won, so
+        // those errors are only logged. Every log is loaded before any is written, so a broken
+        // file cannot stop the updates halfway.
+        let loaded = [
+            daily.map(|_| self.get_daily_log().map(|_| ())),
+            level.as_ref().map(|_| self.get_level_log().map(|_| ())),
+            Some(self.get_statistics().map(|_| ())),
+        ];
+        let mut errors: Vec<QueensError> = loaded
+            .into_iter()
+            .flatten()
+            .filter_map(Result::err)
+            .collect();
+
+        if let (Some(date), Some(Ok(log))) = (daily, self.daily_log.as_mut()) {
+            log.record(date, summary.time);
+            errors.extend(log.store().err());
+        }
+        if let (Some(id), Some(Ok(log))) = (level, self.level_log.as_mut()) {
+            log.record(id);
+            errors.extend(log.store().err());
+        }
+        if let Some(Ok(statistics)) = self.statistics.as_mut() {
+            statistics.record(record);
+            errors.extend(statistics.store().err());
+        }
+        for e in errors {
+            warn!("{}", e);
+        }
+
+        self.game_state = GameState::Won(summary);
+        self.suspended = None;
+        if let Err(e) = save::remove() {
+            warn!("{}", e);
+        }
+        Ok(())
+    }
+
+    // Stop the clock, like when the player looks away from the game
+    pub fn pause(&mut self) {
+        if let Ok(in_game) = self.in_game_mut() {
+            in_game.pause();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Ok(in_game) = self.in_game_mut() {
+            in_game.resume();
+        }
+    }
+
+    pub fn get_elapsed(&self) -> Duration {
+        self.in_game().map_or(Duration::ZERO, |s| s.get_elapsed())
+    }
+
+    // Go back to the main menu, keeping the game to continue later
+    pub fn suspend(&mut self) {
+        if let GameState::InGame(in_game) = std::mem::take(&mut self.game_state) {
+            let mut in_game = in_game;
+            in_game.pause();
+            self.suspended = Some(in_game);
+        }
+    }
+
+    // Whether there is a game to continue, in memory or saved
+    pub fn can_continue(&self) -> bool {
+        self.suspended.is_some() || save::exists()
+    }
+
+    // Continue the suspended game, or else the saved one
+    pub fn continue_game(&mut self) -> QueensResult<()> {
+        if self.suspended.is_none() {
+            // Only the game is taken from the save, the settings and loaded files stay
+            self.suspended = save::load()?.and_then(|saved| saved.suspended);
+        }
+
+        let Some(mut in_game) = self.suspended.take() else {
+            return Err(QueensError::NoSave);
+        };
+        in_game.resume();
+        self.n = in_game.layout.get_n();
+        self.game_state = GameState::InGame(in_game);
+
+        Ok(())
+    }
+
+    // Check that a loaded state fits together
+    pub fn check(&self) -> QueensResult<()> {
+        if let GameState::InGame(in_game) = &self.game_state {
+            in_game.check()?;
+        }
+        if let Some(in_game) = &self.suspended {
+            in_game.check()?;
+        }
+        Ok(())
+    }
+
+    // Whether there is a game to save
+    pub fn has_game(&self) -> bool {
+        self.suspended.is_some() || matches!(self.game_state, GameState::InGame(_))
     }
 
-    pub fn set_game_won(&mut self) {
-        self.game_state = GameState::Won;
+    // Save the current game, which is continued from the main menu once loaded
+    pub fn save(&self) -> QueensResult<()> {
+        let mut state = self.clone();
+        state.suspend();
+
+        match state.suspended {
+            Some(_) => save::store(&state),
+            None => Ok(()),
+        }
     }
 
     pub fn get_layout_type(&self) -> &LayoutType {
         &self.layout_type
     }
 
+    // Also brings n into the sizes the layout type supports
     pub fn set_layout_type(&mut self, layout_type: LayoutType) {
+        let sizes = layout_type.get_sizes();
+        self.n = self.n.clamp(*sizes.start(), *sizes.end());
         self.layout_type = layout_type;
     }
 
+    pub fn get_auto_eliminate(&self) -> bool {
+        self.auto_eliminate
+    }
+
+    pub fn set_auto_eliminate(&mut self, auto_eliminate: bool) {
+        self.auto_eliminate = auto_eliminate;
+    }
+
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
     pub fn set_n(&mut self, n: usize) {
         self.n = n;
     }
@@ -65,33 +400,89 @@ impl State {
         self.in_game().unwrap().get_grid()
     }
 
-    pub fn get_marked(&self) -> Option<usize> {
-        self.in_game().unwrap().get_marked()
+    pub fn get_marked(&self) -> Option<Coord> {
+        self.in_game().ok().and_then(|s| s.get_marked())
     }
 
-    pub fn set_marked(&mut self, marked: Option<usize>) {
-        self.in_game_mut().unwrap().set_marked(marked)
+    pub fn set_marked(&mut self, marked: Option<Coord>) {
+        if let Ok(in_game) = self.in_game_mut() {
+            in_game.set_marked(marked);
+        }
+    }
+
+    // Move the keyboard marker one tile, wrapping around the sides, or all the way to the side
+    // with to_edge. Without a marker it starts in the top left corner.
+    pub fn move_marker(&mut self, direction: Direction, to_edge: bool) {
+        let n = self.n;
+        let marked = match self.get_marked() {
+            Some(coord) if to_edge => coord.to_edge(direction, n),
+            Some(coord) => coord.moved(direction, Movement::Wrap, n),
+            None => Coord::default(),
+        };
+        self.set_marked(Some(marked));
     }
 
     pub fn get_layout(&self) -> Layout {
         self.in_game().unwrap().get_layout()
     }
 
-    pub fn get_tile(&self, x: usize) -> QueensResult<Tile> {
-        self.in_game()?.get_tile(x)
+    // Seed the current layout was generated from
+    pub fn get_game_seed(&self) -> Option<u64> {
+        self.in_game().ok().and_then(|s| s.get_seed())
+    }
+
+    // Difficulty asked for and the one used instead, when a rated layout of it was not found
+    pub fn get_missed_difficulty(&self) -> Option<(Difficulty, Difficulty)> {
+        self.in_game().ok().and_then(|s| s.get_missed_difficulty())
+    }
+
+    pub fn get_tile(&self, coord: Coord) -> QueensResult<Tile> {
+        self.in_game()?.get_tile(coord)
     }
 
-    pub fn set_tile(&mut self, x: usize, tile: Tile) {
-        self.in_game_mut().unwrap().set_tile(x, tile)
+    pub fn flip_tile(&mut self, coord: Coord) -> QueensResult<()> {
+        self.in_game_mut()?.flip_tile(coord)
     }
 
-    pub fn flip_tile(&mut self, c: usize) -> QueensResult<()> {
-        self.in_game_mut()?.flip_tile(c)
+    pub fn toggle_queen(&mut self, coord: Coord) -> QueensResult<()> {
+        self.in_game_mut()?.toggle_queen(coord)
+    }
+
+    pub fn get_hint(&self) -> Option<Hint> {
+        self.in_game().ok().and_then(|s| s.get_hint())
+    }
+
+    pub fn clear(&mut self) -> QueensResult<()> {
+        self.in_game_mut()?.clear()
+    }
+
+    pub fn undo(&mut self) -> QueensResult<()> {
+        self.in_game_mut()?.undo()
+    }
+
+    pub fn redo(&mut self) -> QueensResult<()> {
+        self.in_game_mut()?.redo()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.in_game().is_ok_and(|s| s.can_undo())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.in_game().is_ok_and(|s| s.can_redo())
+    }
+
+    pub fn request_hint(&mut self) -> QueensResult<()> {
+        self.in_game_mut()?.request_hint()
+    }
+
+    pub fn reveal_queen(&mut self) -> QueensResult<()> {
+        self.in_game_mut()?.reveal_queen()
     }
 
     // Get the list of game errors and whether the game has been won.
     pub fn get_win_status(&self) -> QueensResult<(Vec<GameRuleBroken>, bool)> {
-        self.in_game()?.get_win_status(self.n)
+        self.in_game()?.get_win_status()
     }
 }
 
@@ -100,37 +491,246 @@ impl Default for State {
         Self {
             n: 10,
             layout_type: LayoutType::default(),
+            auto_eliminate: false,
             game_state: GameState::default(),
+            seed: None,
+            suspended: None,
+            daily_log: None,
+            statistics: None,
+            packs: None,
+            level_log: None,
+            generation: None,
         }
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct InGameState {
     grid: Grid,
     layout: Layout,
-    marked: Option<usize>,
+    // Bitboards of the layout for the rule checks, built again on the first check after loading
+    #[serde(skip)]
+    board: OnceLock<Board>,
+    // Tile of the keyboard marker
+    marked: Option<Coord>,
+    #[serde(skip)]
+    hint: Option<Hint>,
+    history: History,
+    // Whether placing a queen crosses out the tiles it rules out
+    auto_eliminate: bool,
+    // How the layout was made, none if it was given
+    #[serde(default)]
+    layout_type: Option<LayoutType>,
+    // Seed the layout was generated from, none for the fixed layouts
+    #[serde(default)]
+    seed: Option<u64>,
+    // Difficulty the layout was rated or given by its pack, none without a unique solution
+    #[serde(default)]
+    difficulty: Option<Difficulty>,
+    // Difficulty asked for, when no layout of it was found and a closer one was used
+    #[serde(default)]
+    asked: Option<Difficulty>,
+    // Date of the daily puzzle this is
+    #[serde(default)]
+    daily: Option<NaiveDate>,
+    // Level of a puzzle pack this is
+    #[serde(default)]
+    level: Option<LevelId>,
+    // Player actions so far, for the summary once the game is won
+    #[serde(default)]
+    moves: u32,
+    #[serde(default)]
+    undos: u32,
+    #[serde(default)]
+    hints: u32,
+    // Time played before the clock was last started
+    played: Duration,
+    // When the clock was started, none while the game is paused
+    #[serde(skip)]
+    started: Option<Instant>,
 }
 
 impl InGameState {
-    pub fn new(n: usize, layout_type: LayoutType) -> InGameState {
-        let mut grid = Grid::new(n);
+    pub fn new(n: usize, layout_type: LayoutType, seed: u64) -> QueensResult<InGameState> {
+        Self::generate(n, layout_type, seed, &mut |_| true)
+    }
+
+    // Like new, calling on_attempt while a unique or rated layout is generated with the number of
+    // layouts tried so far. Stops with GenerationCancelled once on_attempt gives false.
+    pub fn generate(
+        n: usize,
+        layout_type: LayoutType,
+        seed: u64,
+        on_attempt: &mut dyn FnMut(usize) -> bool,
+    ) -> QueensResult<InGameState> {
+        let mut layout_type = layout_type;
+        let mut asked = None;
         let layout = match layout_type {
-            LayoutType::Easy => Layout::easy_layout(n),
-            LayoutType::Complex => Layout::complex_layout(n),
-            LayoutType::Generated => Layout::generate_layout(n),
+            LayoutType::Easy => Layout::easy_layout(n)?,
+            LayoutType::Complex => Layout::complex_layout(n)?,
+            LayoutType::Generated => Layout::generate_layout(n, seed)?,
+            LayoutType::GeneratedUnique => {
+                Layout::generate_unique_layout_with(n, seed, &mut || on_attempt(0))?
+            }
+            LayoutType::Rated(difficulty) => {
+                let (layout, rated) =
+                    Layout::generate_rated_layout_with(n, difficulty, seed, on_attempt)?;
+                // Record the difficulty the layout really has
+                if rated != difficulty {
+                    layout_type = LayoutType::Rated(rated);
+                    asked = Some(difficulty);
+                }
+                layout
+            }
+        };
+        let seed = match layout_type {
+            LayoutType::Easy | LayoutType::Complex => None,
+            _ => Some(seed),
         };
 
-        for area in layout.get_areas() {
-            for index in area.get_sections().clone() {
-                grid.set_tile(index, Tile::new(false, area.get_color()));
+        let mut in_game = Self::from_layout(layout)?;
+        in_game.layout_type = Some(layout_type);
+        in_game.seed = seed;
+        in_game.asked = asked;
+
+        Ok(in_game)
+    }
+
+    // Fresh game on a given layout
+    pub fn from_layout(layout: Layout) -> QueensResult<InGameState> {
+        let problems = layout.validate();
+        if !problems.is_empty() {
+            return Err(QueensError::InvalidLayout { problems });
+        }
+
+        let n = layout.get_n();
+        let grid = Grid::from_layout(&layout);
+        let difficulty = Rating::rate(&layout, n).ok().map(|r| r.difficulty);
+
+        if log_enabled!(Level::Debug) {
+            match Solver::new(&layout, n) {
+                Ok(solver) => {
+                    debug!(
+                        "Layout has {} solution(s), counting up to 100",
+                        solver.count_solutions(100)
+                    );
+                    for solution in solver.solutions(Some(2)) {
+                        debug!("Solution: {:?}", solution);
+                    }
+                }
+                Err(e) => debug!("Could not solve layout: {}", e),
+            }
+
+            if let Ok(mut deducer) = Deducer::new(&layout, n) {
+                for step in deducer.solve() {
+                    debug!("{}", step);
+                }
+                if !deducer.is_solved() {
+                    debug!("No further deductions");
+                }
             }
         }
 
-        Self {
+        Ok(Self {
             grid,
+            board: OnceLock::from(Board::new(&layout)?),
             layout,
             marked: None,
+            hint: None,
+            history: History::default(),
+            auto_eliminate: false,
+            layout_type: None,
+            seed: None,
+            difficulty,
+            asked: None,
+            daily: None,
+            level: None,
+            moves: 0,
+            undos: 0,
+            hints: 0,
+            played: Duration::ZERO,
+            started: Some(Instant::now()),
+        })
+    }
+
+    // Check that a loaded game fits together
+    pub fn check(&self) -> QueensResult<()> {
+        let problems = self.layout.validate();
+        if !problems.is_empty() {
+            return Err(QueensError::InvalidLayout { problems });
+        }
+
+        let n = self.layout.get_n();
+        if self.grid.get_n() != n || self.grid.get_data().len() != n.pow(2) {
+            return Err(QueensError::GridSizeMismatch {
+                layout: n,
+                grid: self.grid.get_n(),
+                tiles: self.grid.get_data().len(),
+            });
+        }
+
+        // The tiles are drawn in their own color, while the rules are checked on the layout
+        let colors = Grid::from_layout(&self.layout);
+        for (c, tile) in self.grid.get_data().iter().enumerate() {
+            if tile.get_raw_color() != colors.get_data()[c].get_raw_color() {
+                return Err(QueensError::TileColorMismatch {
+                    coord: self.grid.get_coord(c)?,
+                });
+            }
+        }
+
+        // Undo and redo put these tiles back, so they must keep the color of their tile
+        for m in self.history.get_moves() {
+            let color = match self.grid.get_tile(m.coord) {
+                Ok(tile) => tile.get_raw_color(),
+                Err(_) => return Err(QueensError::InvalidMove { coord: m.coord }),
+            };
+            if m.before.get_raw_color() != color || m.after.get_raw_color() != color {
+                return Err(QueensError::InvalidMove { coord: m.coord });
+            }
+        }
+
+        match self.marked {
+            Some(coord) if coord.row >= n || coord.col >= n => {
+                Err(QueensError::Invalid2DCoordinate { coord, n })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn get_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    // Difficulty asked for and the one used instead, when a rated layout of it was not found
+    pub fn get_missed_difficulty(&self) -> Option<(Difficulty, Difficulty)> {
+        match (self.asked, &self.layout_type) {
+            (Some(asked), Some(LayoutType::Rated(rated))) => Some((asked, *rated)),
+            _ => None,
+        }
+    }
+
+    pub fn get_elapsed(&self) -> Duration {
+        self.played + self.started.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+
+    pub fn get_summary(&self) -> Summary {
+        Summary {
+            time: self.get_elapsed(),
+            moves: self.moves,
+            undos: self.undos,
+            hints: self.hints,
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.played = self.get_elapsed();
+        self.started = None;
+    }
+
+    pub fn resume(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
         }
     }
 
@@ -138,11 +738,11 @@ impl InGameState {
         self.grid.clone()
     }
 
-    pub fn get_marked(&self) -> Option<usize> {
+    pub fn get_marked(&self) -> Option<Coord> {
         self.marked
     }
 
-    pub fn set_marked(&mut self, marked: Option<usize>) {
+    pub fn set_marked(&mut self, marked: Option<Coord>) {
         self.marked = marked;
     }
 
@@ -150,85 +750,400 @@ impl InGameState {
         self.layout.clone()
     }
 
-    pub fn get_tile(&self, x: usize) -> QueensResult<Tile> {
-        self.grid.get_tile(x)
+    pub fn get_tile(&self, coord: Coord) -> QueensResult<Tile> {
+        self.grid.get_tile(coord)
+    }
+
+    // Set a tile as a move of the player, which can be undone
+    pub fn set_tile(&mut self, coord: Coord, tile: Tile) -> QueensResult<()> {
+        let before = self.grid.get_tile(coord)?;
+        self.history.record(Move {
+            coord,
+            before,
+            after: tile,
+        });
+        self.grid.set_tile(coord, tile)?;
+        self.hint = None;
+        Ok(())
+    }
+
+    // Record the following tile changes as one move until end_batch is called
+    pub fn begin_batch(&mut self) {
+        self.history.begin_batch();
     }
 
-    pub fn set_tile(&mut self, x: usize, tile: Tile) {
-        self.grid.set_tile(x, tile);
+    pub fn end_batch(&mut self) {
+        self.history.end_batch();
     }
 
-    pub fn flip_tile(&mut self, c: usize) -> QueensResult<()> {
-        self.grid.get_tile(c).and_then(|tile| {
-            self.grid.set_tile(c, tile.on_click());
+    // Empty every tile as a single move, none if the grid is already empty
+    pub fn clear(&mut self) -> QueensResult<()> {
+        let data = self.grid.get_data();
+        if data.iter().all(|t| t.get_state() == TileState::Empty) {
+            return Ok(());
+        }
+
+        self.begin_batch();
+        let result = self.clear_tiles();
+        self.end_batch();
+        self.moves += 1;
+        result
+    }
+
+    fn clear_tiles(&mut self) -> QueensResult<()> {
+        for (c, tile) in self.grid.get_data().clone().into_iter().enumerate() {
+            if tile.get_state() != TileState::Empty {
+                let coord = self.grid.get_coord(c)?;
+                self.set_tile(coord, Tile::new(TileState::Empty, tile.get_raw_color()))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> QueensResult<()> {
+        if let Some(moves) = self.history.undo() {
+            for m in moves {
+                self.grid.set_tile(m.coord, m.before)?;
+            }
+            self.hint = None;
+            self.undos += 1;
+        }
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> QueensResult<()> {
+        if let Some(moves) = self.history.redo() {
+            for m in moves {
+                self.grid.set_tile(m.coord, m.after)?;
+            }
+            self.hint = None;
+            self.moves += 1;
+        }
+        Ok(())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    pub fn flip_tile(&mut self, coord: Coord) -> QueensResult<()> {
+        let tile = self.grid.get_tile(coord)?;
+        self.change_tile(coord, tile.on_click())?;
+        self.moves += 1;
+        Ok(())
+    }
+
+    pub fn toggle_queen(&mut self, coord: Coord) -> QueensResult<()> {
+        let tile = self.grid.get_tile(coord)?;
+        self.change_tile(coord, tile.toggle_queen())?;
+        self.moves += 1;
+        Ok(())
+    }
+
+    pub fn set_auto_eliminate(&mut self, auto_eliminate: bool) {
+        self.auto_eliminate = auto_eliminate;
+    }
+
+    // Set a tile, crossing out or restoring the tiles a placed or removed queen rules out
+    fn change_tile(&mut self, coord: Coord, tile: Tile) -> QueensResult<()> {
+        let was_queen = self.grid.get_tile(coord)?.is_queen();
+
+        self.begin_batch();
+        let result = self.set_tile(coord, tile).and_then(|()| {
+            if self.auto_eliminate && tile.is_queen() && !was_queen {
+                self.eliminate_around(coord)?;
+            }
+            if self.auto_eliminate && was_queen && !tile.is_queen() {
+                self.restore_eliminated()?;
+            }
             Ok(())
-        })
+        });
+        self.end_batch();
+        result
     }
 
-    // Get the list of game errors and whether the game has been won.
-    pub fn get_win_status(&self, n: usize) -> QueensResult<(Vec<GameRuleBroken>, bool)> {
-        let mut errors = Vec::new();
-        let mut rows = Vec::new();
-        let mut cols = Vec::new();
-        let mut colors = Vec::new();
-
-        for (index, tile) in self.grid.get_data().iter().enumerate() {
-            if tile.is_set() {
-                let color = tile.get_color();
-                let (col, row) = self.grid.split_coordinate(index)?;
-
-                if rows.contains(&row) {
-                    errors.push(GameRuleBroken::Row { row })
-                } else {
-                    rows.push(row);
-                }
+    // Whether a queen on c rules out t: same row, column or area, or touching
+    fn attacks(&self, c: Coord, t: Coord) -> bool {
+        let same_area = match (self.grid.get_tile(c), self.grid.get_tile(t)) {
+            (Ok(a), Ok(b)) => a.get_raw_color() == b.get_raw_color(),
+            _ => false,
+        };
 
-                if cols.contains(&col) {
-                    errors.push(GameRuleBroken::Column { col })
-                } else {
-                    cols.push(col);
-                }
+        c != t && (c.row == t.row || c.col == t.col || same_area || c.touches(&t))
+    }
 
-                if colors.contains(&color) {
-                    errors.push(GameRuleBroken::Area {
-                        area: self
-                            .layout
-                            .get_area(self.grid.merge_coordinate(col, row)?)?,
-                    })
-                } else {
-                    colors.push(color)
-                }
+    fn eliminate_around(&mut self, c: Coord) -> QueensResult<()> {
+        for (t, tile) in self.grid.get_data().clone().into_iter().enumerate() {
+            let t = self.grid.get_coord(t)?;
+            if tile.get_state() == TileState::Empty && self.attacks(c, t) {
+                self.set_tile(t, Tile::new(TileState::AutoCrossed, tile.get_raw_color()))?;
+            }
+        }
+        Ok(())
+    }
 
-                if index % n != n - 1 {
-                    // is not all the way to the right
-                    let below_right = index + n + 1;
-                    if self.grid.get_tile(below_right).is_ok_and(|t| t.is_set()) {
-                        errors.push(GameRuleBroken::Diagonal {
-                            c1: index,
-                            c2: below_right,
-                        })
-                    }
-                }
+    // Empty the automatic crosses no queen rules out anymore
+    fn restore_eliminated(&mut self) -> QueensResult<()> {
+        let data = self.grid.get_data().clone();
+        let queens = (0..data.len())
+            .filter(|&q| data[q].is_queen())
+            .map(|q| self.grid.get_coord(q))
+            .collect::<QueensResult<Vec<Coord>>>()?;
 
-                if index % n != 0 {
-                    // is not all the way to the left
-                    let below_left = index + n - 1;
-                    if self.grid.get_tile(below_left).is_ok_and(|t| t.is_set()) {
-                        errors.push(GameRuleBroken::Diagonal {
-                            c1: index,
-                            c2: below_left,
-                        })
-                    }
-                }
+        for (t, tile) in data.into_iter().enumerate() {
+            let t = self.grid.get_coord(t)?;
+            if tile.get_state() == TileState::AutoCrossed
+                && !queens.iter().any(|&q| self.attacks(q, t))
+            {
+                self.set_tile(t, Tile::new(TileState::Empty, tile.get_raw_color()))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_hint(&self) -> Option<Hint> {
+        self.hint.clone()
+    }
+
+    pub fn request_hint(&mut self) -> QueensResult<()> {
+        self.hint = Some(Hint::find(&self.layout, &self.grid)?);
+        self.hints += 1;
+        Ok(())
+    }
+
+    // Stronger hint placing the next queen on the grid
+    pub fn reveal_queen(&mut self) -> QueensResult<()> {
+        if let Some(coord) = Hint::next_queen(&self.layout, &self.grid)? {
+            let tile = self.grid.get_tile(coord)?;
+            if !tile.is_queen() {
+                self.change_tile(coord, tile.toggle_queen())?;
             }
         }
+        self.hints += 1;
+        self.hint = None;
+        Ok(())
+    }
+
+    // Get the list of game errors and whether the game has been won.
+    pub fn get_win_status(&self) -> QueensResult<(Vec<GameRuleBroken>, bool)> {
+        check_rules_on(self.get_board()?, &self.layout, &self.grid)
+    }
+
+    fn get_board(&self) -> QueensResult<&Board> {
+        if let Some(board) = self.board.get() {
+            return Ok(board);
+        }
+        let board = Board::new(&self.layout)?;
+        Ok(self.board.get_or_init(|| board))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_game() -> InGameState {
+        InGameState::from_layout(Layout::easy_layout(5).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn check_accepts_played_games() {
+        let mut in_game = in_game();
+        in_game.flip_tile(Coord { row: 0, col: 3 }).unwrap();
+        in_game.toggle_queen(Coord { row: 2, col: 2 }).unwrap();
+        in_game.undo().unwrap();
+
+        assert!(in_game.check().is_ok());
+    }
+
+    #[test]
+    fn check_rejects_moves_outside_the_grid() {
+        let mut in_game = in_game();
+        let tile = in_game.get_tile(Coord::default()).unwrap();
+        let coord = Coord { row: 5, col: 0 };
+        in_game.history.record(Move {
+            coord,
+            before: tile,
+            after: tile.toggle_queen(),
+        });
+
+        assert!(matches!(
+            in_game.check(),
+            Err(QueensError::InvalidMove { coord: c }) if c == coord
+        ));
+    }
+
+    #[test]
+    fn check_rejects_moves_of_another_color() {
+        let mut in_game = in_game();
+        let tile = in_game.get_tile(Coord::default()).unwrap();
+        let coord = Coord { row: 4, col: 0 };
+        in_game.history.record(Move {
+            coord,
+            before: tile,
+            after: tile.toggle_queen(),
+        });
+
+        assert!(matches!(
+            in_game.check(),
+            Err(QueensError::InvalidMove { coord: c }) if c == coord
+        ));
+    }
+
+    #[test]
+    fn loaded_games_build_the_board_again() {
+        let mut in_game = in_game();
+        in_game.toggle_queen(Coord::default()).unwrap();
+        in_game.toggle_queen(Coord { row: 0, col: 1 }).unwrap();
+
+        let json = serde_json::to_string(&in_game).unwrap();
+        let loaded: InGameState = serde_json::from_str(&json).unwrap();
+
+        assert!(loaded.board.get().is_none());
+        let (broken, won) = loaded.get_win_status().unwrap();
+        assert!(!broken.is_empty() && !won);
+        assert!(loaded.board.get().is_some());
+    }
+
+    #[test]
+    fn check_rejects_a_grid_of_another_size() {
+        let mut in_game = in_game();
+        in_game.grid = Grid::new(4);
+
+        assert!(matches!(
+            in_game.check(),
+            Err(QueensError::GridSizeMismatch {
+                layout: 5,
+                grid: 4,
+                tiles: 16
+            })
+        ));
+    }
+
+    #[test]
+    fn check_rejects_tiles_not_colored_like_the_layout() {
+        let mut in_game = in_game();
+        let coord = Coord { row: 2, col: 3 };
+        let tile = in_game.grid.get_tile(coord).unwrap();
+        let color = (tile.get_raw_color() + 1) % 5;
+        in_game
+            .grid
+            .set_tile(coord, Tile::new(tile.get_state(), color))
+            .unwrap();
+
+        assert!(matches!(
+            in_game.check(),
+            Err(QueensError::TileColorMismatch { coord: c }) if c == coord
+        ));
+    }
+
+    #[test]
+    fn clearing_an_empty_grid_is_no_move() {
+        let mut in_game = in_game();
+        in_game.clear().unwrap();
+        assert_eq!(in_game.get_summary().moves, 0);
+        assert!(!in_game.can_undo());
+
+        in_game.toggle_queen(Coord::default()).unwrap();
+        in_game.clear().unwrap();
+        assert_eq!(in_game.get_summary().moves, 2);
+        assert!(!in_game.grid.get_tile(Coord::default()).unwrap().is_queen());
+    }
+
+    #[test]
+    fn suspended_games_continue_where_they_were_left() {
+        let mut state = State::default();
+        state.load_layout(Layout::easy_layout(5).unwrap()).unwrap();
+        state.toggle_queen(Coord { row: 1, col: 2 }).unwrap();
+        state.set_marked(Some(Coord { row: 3, col: 4 }));
+
+        state.suspend();
+        assert!(matches!(state.get_game_state(), GameState::MainMenu));
+        assert!(matches!(
+            state.get_tile(Coord::default()),
+            Err(QueensError::NotIngame)
+        ));
+        assert!(state.can_continue());
+
+        state.continue_game().unwrap();
+        assert!(matches!(state.get_game_state(), GameState::InGame(_)));
+        assert!(state.get_tile(Coord { row: 1, col: 2 }).unwrap().is_queen());
+        assert_eq!(state.get_marked(), Some(Coord { row: 3, col: 4 }));
+        assert!(state.can_undo());
+        assert_eq!(state.get_n(), 5);
+    }
+
+    fn states(in_game: &InGameState) -> Vec<TileState> {
+        in_game
+            .grid
+            .get_data()
+            .iter()
+            .map(|t| t.get_state())
+            .collect()
+    }
+
+    #[test]
+    fn removing_a_queen_restores_only_its_own_crosses() {
+        let mut in_game = in_game();
+        in_game.set_auto_eliminate(true);
+        let (a, b) = (Coord { row: 0, col: 0 }, Coord { row: 2, col: 2 });
+        // A cross of the player is never restored
+        in_game.flip_tile(Coord { row: 2, col: 4 }).unwrap();
+
+        in_game.toggle_queen(a).unwrap();
+        let only_a = states(&in_game);
+        in_game.toggle_queen(b).unwrap();
+        let both = states(&in_game);
+
+        // Both queens rule out these tiles
+        for coord in [(0, 2), (2, 0), (1, 1)].map(|(row, col)| Coord { row, col }) {
+            assert_eq!(
+                in_game.get_tile(coord).unwrap().get_state(),
+                TileState::AutoCrossed
+            );
+        }
+        assert_eq!(
+            in_game
+                .get_tile(Coord { row: 2, col: 4 })
+                .unwrap()
+                .get_state(),
+            TileState::Crossed
+        );
+
+        in_game.toggle_queen(b).unwrap();
+        assert_eq!(states(&in_game), only_a);
+
+        // Removing the queen and its restored crosses is a single step
+        in_game.undo().unwrap();
+        assert_eq!(states(&in_game), both);
+        in_game.redo().unwrap();
+        assert_eq!(states(&in_game), only_a);
+    }
+
+    #[test]
+    fn placing_a_queen_is_undone_with_its_crosses() {
+        let mut in_game = in_game();
+        in_game.set_auto_eliminate(true);
+        let empty = states(&in_game);
+
+        in_game.toggle_queen(Coord { row: 1, col: 1 }).unwrap();
+        let placed = states(&in_game);
+        assert_eq!(
+            placed
+                .iter()
+                .filter(|&&s| s == TileState::AutoCrossed)
+                .count(),
+            12
+        );
 
-        Ok((
-            errors.clone(),
-            (rows.len() == n)
-                && (cols.len() == n)
-                && (colors.len() == n)
-                && errors.clone().is_empty(),
-        ))
+        in_game.undo().unwrap();
+        assert_eq!(states(&in_game), empty);
+        assert!(!in_game.can_undo());
+        in_game.redo().unwrap();
+        assert_eq!(states(&in_game), placed);
     }
 }
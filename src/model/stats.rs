@@ -0,0 +1,184 @@
+use crate::errors::QueensResult;
+use crate::model::daily::streak;
+use crate::model::difficulty::Difficulty;
+use crate::model::layout::LayoutType;
+use crate::model::save;
+use crate::model::state::Summary;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const STATS_FILE: &str = "stats.json";
+
+// A won game
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub date: NaiveDate,
+    pub n: usize,
+    // None for layouts that were not generated by the game, like custom ones
+    pub layout_type: Option<LayoutType>,
+    // None for layouts without a unique solution, and games won before difficulties were kept
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    pub seed: Option<u64>,
+    pub daily: bool,
+    pub summary: Summary,
+}
+
+// Results of the games of one size, layout type and difficulty
+#[derive(Clone, Debug)]
+pub struct GroupStats {
+    pub n: usize,
+    pub layout_type: Option<LayoutType>,
+    pub difficulty: Option<Difficulty>,
+    pub played: usize,
+    pub best: Duration,
+    pub average: Duration,
+    pub fewest_hints: u32,
+    // Days in a row with a won game
+    pub streak: usize,
+}
+
+// Every game won on this machine
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Statistics {
+    records: Vec<GameRecord>,
+}
+
+impl Statistics {
+    pub fn load() -> QueensResult<Statistics> {
+        Ok(save::read_json(STATS_FILE)?.unwrap_or_default())
+    }
+
+    pub fn store(&self) -> QueensResult<()> {
+        save::write_json(STATS_FILE, self)
+    }
+
+    pub fn record(&mut self, record: GameRecord) {
+        self.records.push(record);
+    }
+
+    pub fn get_records(&self) -> &Vec<GameRecord> {
+        &self.records
+    }
+
+    // Results per size, layout type and difficulty, smallest size first
+    pub fn groups(&self, today: NaiveDate) -> Vec<GroupStats> {
+        let mut keys: Vec<(usize, Option<LayoutType>, Option<Difficulty>)> = Vec::new();
+        for record in self.records.iter() {
+            let key = (record.n, record.layout_type.clone(), record.difficulty);
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys.sort_by_key(|(n, layout_type, difficulty)| {
+            (*n, layout_type.as_ref().map(|t| t.to_string()), *difficulty)
+        });
+
+        keys.into_iter()
+            .map(|(n, layout_type, difficulty)| {
+                let records: Vec<&GameRecord> = self
+                    .records
+                    .iter()
+                    .filter(|r| {
+                        r.n == n && r.layout_type == layout_type && r.difficulty == difficulty
+                    })
+                    .collect();
+                let times: Vec<Duration> = records.iter().map(|r| r.summary.time).collect();
+                let dates: Vec<NaiveDate> = records.iter().map(|r| r.date).collect();
+
+                GroupStats {
+                    n,
+                    layout_type,
+                    difficulty,
+                    played: records.len(),
+                    best: times.iter().copied().min().unwrap_or_default(),
+                    average: times.iter().sum::<Duration>() / records.len().max(1) as u32,
+                    fewest_hints: records
+                        .iter()
+                        .map(|r| r.summary.hints)
+                        .min()
+                        .unwrap_or_default(),
+                    streak: streak(&dates, today),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, d).unwrap()
+    }
+
+    fn record(date: NaiveDate, n: usize, difficulty: Option<Difficulty>, secs: u64) -> GameRecord {
+        GameRecord {
+            date,
+            n,
+            layout_type: difficulty.map(LayoutType::Rated),
+            difficulty,
+            seed: None,
+            daily: false,
+            summary: Summary {
+                time: Duration::from_secs(secs),
+                moves: 10,
+                undos: 0,
+                hints: secs as u32 / 10,
+            },
+        }
+    }
+
+    #[test]
+    fn groups_by_size_layout_and_difficulty() {
+        let mut statistics = Statistics::default();
+        statistics.record(record(day(1), 8, Some(Difficulty::Hard), 30));
+        statistics.record(record(day(2), 6, Some(Difficulty::Easy), 20));
+        statistics.record(record(day(3), 8, Some(Difficulty::Hard), 50));
+        statistics.record(record(day(3), 8, Some(Difficulty::Easy), 10));
+        statistics.record(record(day(3), 8, None, 40));
+
+        let groups = statistics.groups(day(3));
+        let keys: Vec<(usize, Option<Difficulty>)> =
+            groups.iter().map(|g| (g.n, g.difficulty)).collect();
+        assert_eq!(
+            keys,
+            vec![
+                (6, Some(Difficulty::Easy)),
+                (8, None),
+                (8, Some(Difficulty::Easy)),
+                (8, Some(Difficulty::Hard)),
+            ]
+        );
+
+        let hard = &groups[3];
+        assert_eq!(hard.played, 2);
+        assert_eq!(hard.best, Duration::from_secs(30));
+        assert_eq!(hard.average, Duration::from_secs(40));
+        assert_eq!(hard.fewest_hints, 3);
+    }
+
+    #[test]
+    fn streaks_are_kept_per_size() {
+        let mut statistics = Statistics::default();
+        for d in 1..=4 {
+            statistics.record(record(day(d), 8, Some(Difficulty::Medium), 60));
+        }
+        // A gap on the 3rd breaks the streak of the smaller grid
+        for d in [1, 2, 4] {
+            statistics.record(record(day(d), 6, Some(Difficulty::Medium), 60));
+        }
+
+        let groups = statistics.groups(day(4));
+        assert_eq!(groups[0].n, 6);
+        assert_eq!(groups[0].streak, 1);
+        assert_eq!(groups[1].n, 8);
+        assert_eq!(groups[1].streak, 4);
+
+        // Today without a game yet keeps the streak up to yesterday
+        assert_eq!(statistics.groups(day(5))[1].streak, 4);
+        assert_eq!(statistics.groups(day(6))[1].streak, 0);
+    }
+}
@@ -1,53 +1,62 @@
-use crate::distinct_colors::{get_distinct_checked_color, get_distinct_color};
-use eframe::egui::{vec2, Button, Color32, Response, RichText, Ui, Vec2, Widget};
-
-pub static TILE_SIZE: Vec2 = vec2(32.0, 32.0);
+use serde::{Deserialize, Serialize};
+
+// What the player has marked a tile as
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TileState {
+    #[default]
+    Empty,
+    // Ruled out by the player
+    Crossed,
+    // Ruled out automatically by a placed queen
+    AutoCrossed,
+    Queen,
+}
 
-#[derive(Clone, Debug, Copy, Default)]
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Tile {
-    set: bool,
+    state: TileState,
     color: u8,
 }
 
 impl Tile {
-    pub fn new(set: bool, color: u8) -> Self {
-        Self { set, color }
+    pub fn new(state: TileState, color: u8) -> Self {
+        Self { state, color }
     }
 
+    // Cycles empty -> crossed -> queen -> empty
     pub fn on_click(&self) -> Self {
-        Self {
-            set: !self.set,
-            color: self.color,
-        }
+        let state = match self.state {
+            TileState::Empty => TileState::Crossed,
+            TileState::Crossed | TileState::AutoCrossed => TileState::Queen,
+            TileState::Queen => TileState::Empty,
+        };
+
+        Self::new(state, self.color)
+    }
+
+    // Places or removes a queen directly
+    pub fn toggle_queen(&self) -> Self {
+        let state = match self.state {
+            TileState::Queen => TileState::Empty,
+            _ => TileState::Queen,
+        };
+
+        Self::new(state, self.color)
     }
 
     pub fn get_raw_color(&self) -> u8 {
         self.color
     }
 
-    pub fn get_color(&self) -> Color32 {
-        if self.set {
-            get_distinct_checked_color(self.color)
-        } else {
-            get_distinct_color(self.color)
-        }
+    pub fn get_state(&self) -> TileState {
+        self.state
     }
 
-    pub fn is_set(&self) -> bool {
-        self.set
+    pub fn is_queen(&self) -> bool {
+        self.state == TileState::Queen
     }
-}
 
-impl Widget for Tile {
-    fn ui(self, ui: &mut Ui) -> Response {
-        let button = Button::new(if self.set {
-            RichText::new("X").color(Color32::BLACK)
-        } else {
-            RichText::from("").color(Color32::BLACK)
-        })
-        .min_size(TILE_SIZE)
-        .fill(self.get_color());
-
-        button.ui(ui)
+    pub fn is_crossed(&self) -> bool {
+        matches!(self.state, TileState::Crossed | TileState::AutoCrossed)
     }
 }
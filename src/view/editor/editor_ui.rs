@@ -0,0 +1,179 @@
+use crate::view::tile_widget::{get_distinct_color, TILE_SIZE};
+use eframe::egui::{
+    Align2, Button, CentralPanel, Color32, Context, CornerRadius, FontId, Key, RichText, Sense,
+    Slider, Stroke, StrokeKind, Ui, Vec2,
+};
+use queens::errors::QueensResult;
+use queens::model::board::{MAX_N, MIN_N};
+use queens::model::coord::Coord;
+use queens::model::editor::{Editor, SOLUTION_LIMIT};
+use queens::model::format::symbol;
+use queens::model::state::State;
+
+#[derive(Default)]
+pub struct EditorUi {
+    // File name as typed by the player
+    name: String,
+    // Outcome of the last save, or why it failed
+    message: Option<Result<String, String>>,
+}
+
+impl EditorUi {
+    pub fn render(&mut self, ctx: &Context, state: &mut State) -> QueensResult<()> {
+        let editor = state.get_editor_mut()?;
+        let mut res = Ok(());
+        let mut play = false;
+        let mut back = false;
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Editor");
+
+            let mut n = editor.get_n();
+            ui.add(Slider::new(&mut n, MIN_N..=MAX_N).text("Size"));
+            if n != editor.get_n() {
+                editor.set_n(n);
+            }
+
+            Self::render_brushes(ui, editor);
+            res = Self::render_grid(ui, editor);
+
+            let problems = editor.validate();
+            if problems.is_empty() {
+                ui.colored_label(Color32::GREEN, "The layout is valid");
+            }
+            for problem in problems.iter() {
+                ui.colored_label(Color32::RED, problem.to_string());
+            }
+
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(problems.is_empty(), Button::new("Count solutions"))
+                    .clicked()
+                {
+                    res = editor.solve().map(|_| ());
+                }
+                match editor.get_solutions() {
+                    Some(0) => ui.label("No solution"),
+                    Some(1) => ui.label("One solution"),
+                    Some(SOLUTION_LIMIT) => {
+                        ui.label(format!("At least {} solutions", SOLUTION_LIMIT))
+                    }
+                    Some(count) => ui.label(format!("{} solutions", count)),
+                    None => ui.label(""),
+                };
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut self.name);
+                for (json, text) in [(false, "Save as text"), (true, "Save as JSON")] {
+                    if ui
+                        .add_enabled(problems.is_empty(), Button::new(text))
+                        .clicked()
+                    {
+                        self.message = Some(
+                            editor
+                                .store(&self.name, json)
+                                .map(|path| format!("Saved to {}", path))
+                                .map_err(|e| e.to_string()),
+                        );
+                    }
+                }
+            });
+            match &self.message {
+                Some(Ok(message)) => {
+                    ui.label(message);
+                }
+                Some(Err(message)) => {
+                    ui.colored_label(Color32::RED, message);
+                }
+                None => {}
+            }
+
+            ui.horizontal(|ui| {
+                play = ui
+                    .add_enabled(problems.is_empty(), Button::new("Play"))
+                    .clicked();
+                back = ui.button("Back").clicked();
+            });
+        });
+        res?;
+
+        if play {
+            let layout = editor.get_layout();
+            return state.load_layout(layout);
+        }
+        if back || ctx.input(|x| x.key_pressed(Key::Escape)) {
+            state.show_main_menu();
+        }
+
+        Ok(())
+    }
+
+    // A swatch per area color, the one painted with outlined
+    fn render_brushes(ui: &mut Ui, editor: &mut Editor) {
+        ui.horizontal(|ui| {
+            ui.label("Brush");
+            for color in 0..editor.get_n() as u8 {
+                let selected = color == editor.get_brush();
+                let button = Button::new(RichText::new(symbol(color)).color(Color32::BLACK))
+                    .min_size(TILE_SIZE * 0.75)
+                    .fill(get_distinct_color(color))
+                    .stroke(if selected {
+                        Stroke::new(2.0, Color32::WHITE)
+                    } else {
+                        Stroke::NONE
+                    });
+                if ui.add(button).clicked() {
+                    editor.set_brush(color);
+                }
+            }
+        });
+    }
+
+    // Tiles are painted by clicking or dragging over them
+    fn render_grid(ui: &mut Ui, editor: &mut Editor) -> QueensResult<()> {
+        let n = editor.get_n();
+        let painting = ui.input(|x| x.pointer.primary_down());
+
+        ui.spacing_mut().item_spacing = Vec2::splat(2.0);
+        for row in 0..n {
+            let mut res = Ok(());
+            ui.horizontal(|ui| {
+                for col in 0..n {
+                    let coord = Coord { row, col };
+                    let (rect, response) = ui.allocate_exact_size(TILE_SIZE, Sense::click());
+
+                    let color = match editor.get_color(coord) {
+                        Ok(color) => color,
+                        Err(e) => {
+                            res = Err(e);
+                            return;
+                        }
+                    };
+                    ui.painter().rect(
+                        rect,
+                        CornerRadius::same(4),
+                        get_distinct_color(color),
+                        Stroke::new(1.0, Color32::BLACK),
+                        StrokeKind::Inside,
+                    );
+                    ui.painter().text(
+                        rect.center(),
+                        Align2::CENTER_CENTER,
+                        symbol(color),
+                        FontId::monospace(14.0),
+                        Color32::BLACK,
+                    );
+
+                    if response.clicked() || (painting && ui.rect_contains_pointer(rect)) {
+                        res = editor.paint(coord);
+                    }
+                }
+            });
+            res?;
+        }
+
+        Ok(())
+    }
+}
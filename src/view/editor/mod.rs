@@ -0,0 +1 @@
+pub mod editor_ui;
@@ -0,0 +1,57 @@
+use crate::view::format_duration;
+use eframe::egui::{CentralPanel, Context, Key, ProgressBar};
+use queens::errors::QueensResult;
+use queens::model::layout::rating_attempts;
+use queens::model::state::State;
+use std::time::Duration;
+
+// How often to check whether the game is ready
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Default)]
+pub struct GeneratingUi {}
+
+impl GeneratingUi {
+    pub fn render(&self, ctx: &Context, state: &mut State) -> QueensResult<()> {
+        state.poll_generation()?;
+        let Some(generation) = state.get_generation() else {
+            return Ok(());
+        };
+
+        let mut cancel = false;
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Generating a layout");
+            ui.label(format!(
+                "{} with n = {}",
+                generation.get_layout_type(),
+                generation.get_n()
+            ));
+
+            match generation.get_attempts() {
+                Some(attempts) => {
+                    let max = rating_attempts(generation.get_n());
+                    ui.add(
+                        ProgressBar::new(attempts as f32 / max as f32)
+                            .text(format!("Tried {} of at most {} layouts", attempts, max)),
+                    );
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+            ui.label(format!(
+                "Time: {}",
+                format_duration(generation.get_elapsed())
+            ));
+
+            cancel = ui.button("Cancel").clicked();
+        });
+
+        if cancel || ctx.input(|x| x.key_pressed(Key::Escape)) {
+            state.cancel_generation();
+        }
+
+        ctx.request_repaint_after(POLL_INTERVAL);
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
-use crate::errors::QueensResult;
-use crate::model::state::{GameState, State};
-use crate::model::tile::Tile;
+use crate::view::tile_widget::TileWidget;
 use eframe::egui::{Ui, Vec2};
+use queens::errors::QueensResult;
+use queens::model::coord::Coord;
+use queens::model::state::State;
+use queens::model::tile::Tile;
 
 // Grid ui
 pub struct GridUi {}
@@ -17,15 +19,9 @@ impl GridUi {
             for row in 0..state.get_n() {
                 ui.horizontal(|ui| {
                     for col in 0..state.get_n() {
-                        res = res.and_then(|_| {
-                            let coord = state.get_grid().merge_coordinate(col, row)?;
-
-                            let tile: Tile = state.get_tile(coord)?;
-                            if ui.add(tile).clicked() {
-                                state.flip_tile(coord)?;
-                            }
-                            Ok(())
-                        });
+                        if res.is_ok() {
+                            res = Self::render_tile(ui, state, Coord { row, col });
+                        }
                     }
                 });
             }
@@ -33,4 +29,17 @@ impl GridUi {
 
         res
     }
+
+    fn render_tile(ui: &mut Ui, state: &mut State, coord: Coord) -> QueensResult<()> {
+        let tile: Tile = state.get_tile(coord)?;
+        let response = ui.add(TileWidget(tile));
+        let queen_modifier = ui.input(|x| x.modifiers.shift);
+
+        if response.secondary_clicked() || (response.clicked() && queen_modifier) {
+            state.toggle_queen(coord)?;
+        } else if response.clicked() {
+            state.flip_tile(coord)?;
+        }
+        Ok(())
+    }
 }
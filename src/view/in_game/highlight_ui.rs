@@ -1,114 +1,101 @@
-use crate::errors::QueensResult;
-use crate::model::layout::{section, Area, Layout};
-use crate::model::state::{InGameState, State};
-use crate::model::tile::{Tile, TILE_SIZE};
-use eframe::egui::CursorIcon::Default;
-use eframe::egui::{vec2, Color32, CornerRadius, Pos2, Rect, Stroke, StrokeKind, Ui, Vec2};
+use crate::view::tile_widget::TILE_SIZE;
+use eframe::egui::{Color32, Pos2, Stroke, Ui, Vec2};
+use queens::model::coord::{Coord, Direction};
+use queens::model::game_rule_broken::GameRuleBroken;
+use queens::model::hint::Hint;
+use queens::model::state::State;
 
 #[derive(Default)]
 pub struct HighlightUI {}
 
 impl HighlightUI {
-    pub fn render(ui: &mut Ui, state: State) -> QueensResult<()> {
-        Self::render_areas(ui, state.clone())?;
-        Self::render_keyboard_mark(ui, state)?;
-        Ok(())
+    // Outline the areas, the broken rules, the hint and the keyboard marker
+    pub fn render(ui: &mut Ui, state: &State, broken: &[GameRuleBroken]) {
+        let n = state.get_n();
+
+        Self::render_areas(ui, state, n);
+        Self::render_conflicts(ui, broken, n);
+        Self::render_hint(ui, state, n);
+        Self::render_keyboard_mark(ui, state, n);
+    }
+
+    fn render_hint(ui: &mut Ui, state: &State, n: usize) {
+        match state.get_hint() {
+            Some(Hint::Step(step)) => {
+                Self::highlight(ui, n, &step.cause, Color32::YELLOW);
+                Self::highlight(ui, n, &step.eliminated, Color32::RED);
+                Self::highlight(ui, n, &step.queens, Color32::GREEN);
+            }
+            Some(Hint::Mistakes { tiles }) => {
+                Self::highlight(ui, n, &tiles, Color32::RED);
+            }
+            Some(Hint::NoDeduction) | None => {}
+        }
     }
 
-    fn render_keyboard_mark(ui: &mut Ui, state: State) -> QueensResult<()> {
-        if let Some(i) = state.get_marked() {
-            Self::highlight(ui, state, Area::from_usize(i, 0), Color32::GRAY)?;
+    // Outline every broken rule, and the queens breaking it
+    fn render_conflicts(ui: &mut Ui, broken: &[GameRuleBroken], n: usize) {
+        for rule in broken {
+            for coord in rule.get_queens() {
+                Self::highlight(ui, n, &[coord], Color32::RED);
+            }
+            Self::highlight(ui, n, &rule.tiles(n), Color32::RED);
         }
-        Ok(())
     }
 
-    fn render_areas(ui: &mut Ui, state: State) -> QueensResult<()> {
+    fn render_keyboard_mark(ui: &mut Ui, state: &State, n: usize) {
+        if let Some(coord) = state.get_marked() {
+            Self::highlight(ui, n, &[coord], Color32::WHITE);
+        }
+    }
+
+    fn render_areas(ui: &mut Ui, state: &State, n: usize) {
         for area in state.get_layout().get_areas() {
-            Self::highlight(ui, state.clone(), area.clone(), Color32::GRAY)?;
+            Self::highlight(ui, n, &area.get_coords(n), Color32::WHITE);
         }
-        Ok(())
     }
 
-    pub fn highlight(
-        ui: &mut Ui,
-        state: State,
-        area: Area,
-        highlight_color: Color32,
-    ) -> QueensResult<()> {
+    pub fn highlight(ui: &mut Ui, n: usize, tiles: &[Coord], highlight_color: Color32) {
         // backgroundcolor, bordercolor
         let window_margin = ui.spacing().window_margin;
         let pad = window_margin.leftf();
-        let n = state.get_n();
 
         let upper_left_corner = Pos2::new(pad, pad) + Vec2::new(2.5, 2.5);
 
         ui.spacing_mut().item_spacing = Vec2::splat(window_margin.leftf());
 
-        for column in 0..n {
-            for row in 0..n {
-                if area
-                    .get_sections()
-                    .contains(&state.get_grid().merge_coordinate(column, row)?)
-                {
-                    let tile_side = TILE_SIZE.x;
-                    let upper_left = upper_left_corner
-                        + Vec2::new(
-                            column as f32 * (tile_side + pad) - pad / 2f32,
-                            row as f32 * (tile_side + pad) - pad / 2f32,
-                        );
-                    let upper_right = upper_left + Vec2::new(tile_side + 2f32 + pad / 2f32, 0f32);
-                    let bottom_right = upper_left
-                        + Vec2::new(tile_side + 2f32 + pad / 2f32, tile_side + 2f32 + pad / 2f32);
-                    let bottom_left = upper_left + Vec2::new(0f32, tile_side + 2f32 + pad / 2f32);
+        for &coord in tiles {
+            let tile_side = TILE_SIZE.x;
+            let upper_left = upper_left_corner
+                + Vec2::new(
+                    coord.col as f32 * (tile_side + pad) - pad / 2f32,
+                    coord.row as f32 * (tile_side + pad) - pad / 2f32,
+                );
+            let upper_right = upper_left + Vec2::new(tile_side + 2f32 + pad / 2f32, 0f32);
+            let bottom_right = upper_left
+                + Vec2::new(tile_side + 2f32 + pad / 2f32, tile_side + 2f32 + pad / 2f32);
+            let bottom_left = upper_left + Vec2::new(0f32, tile_side + 2f32 + pad / 2f32);
 
-                    // TODO coordinate type!
-                    if !(column != 0
-                        && state
-                            .get_grid()
-                            .merge_coordinate(column - 1, row)
-                            .is_ok_and(|t| area.get_sections().contains(&t)))
-                    {
-                        ui.painter().line_segment(
-                            [upper_left, bottom_left],
-                            Stroke::new(1.0, Color32::WHITE),
-                        );
-                    }
-                    if !(row != 0
-                        && state
-                            .get_grid()
-                            .merge_coordinate(column, row - 1)
-                            .is_ok_and(|t| area.get_sections().contains(&t)))
-                    {
-                        ui.painter().line_segment(
-                            [upper_left, upper_right],
-                            Stroke::new(1.0, Color32::WHITE),
-                        );
-                    }
-                    if !state
-                        .get_grid()
-                        .merge_coordinate(column + 1, row)
-                        .is_ok_and(|t| area.get_sections().contains(&t))
-                    {
-                        ui.painter().line_segment(
-                            [upper_right, bottom_right],
-                            Stroke::new(1.0, Color32::WHITE),
-                        );
-                    }
-                    if !state
-                        .get_grid()
-                        .merge_coordinate(column, row + 1)
-                        .is_ok_and(|t| area.get_sections().contains(&t))
-                    {
-                        ui.painter().line_segment(
-                            [bottom_left, bottom_right],
-                            Stroke::new(1.0, Color32::WHITE),
-                        );
-                    }
-
-                    // also quadratic bezier curves for rounded corners?
+            // Draw the sides where the area ends
+            for direction in Direction::ALL {
+                let inside = coord
+                    .step(direction, n)
+                    .is_some_and(|next| tiles.contains(&next));
+                if inside {
+                    continue;
                 }
+
+                let side = match direction {
+                    Direction::Left => [upper_left, bottom_left],
+                    Direction::Up => [upper_left, upper_right],
+                    Direction::Right => [upper_right, bottom_right],
+                    Direction::Down => [bottom_left, bottom_right],
+                };
+                ui.painter()
+                    .line_segment(side, Stroke::new(1.0, highlight_color));
             }
+
+            // also quadratic bezier curves for rounded corners?
         }
-        Ok(())
     }
 }
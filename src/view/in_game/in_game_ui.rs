@@ -1,33 +1,78 @@
-use crate::errors::{QueensError, QueensResult};
-use crate::model::state::State;
+use crate::view::format_duration;
 use crate::view::in_game::grid_ui::GridUi;
 use crate::view::in_game::highlight_ui::HighlightUI;
 use crate::view::in_game::underlay_ui::UnderlayUi;
-use eframe::egui::{Align2, CentralPanel, Context, Key, Vec2, Window};
+use eframe::egui::{Align2, Button, CentralPanel, Context, Key, Modifiers, Ui, Vec2, Window};
+use queens::errors::QueensResult;
+use queens::model::coord::Direction;
+use queens::model::state::State;
+use std::time::Duration;
 
 #[derive(Default)]
-pub struct InGameUi {}
+pub struct InGameUi {
+    // The broken rules are outlined on the grid, the list is optional
+    show_rule_list: bool,
+}
 
 impl InGameUi {
-    pub fn render(&self, ctx: &Context, state: &mut State) -> QueensResult<()> {
+    pub fn render(&mut self, ctx: &Context, state: &mut State) -> QueensResult<()> {
         let panel = CentralPanel::default();
 
-        self.handle_keyboard_input(ctx, state)?;
+        // The clock only runs while the player can see the game
+        if ctx.input(|x| x.focused) {
+            state.resume();
+        } else {
+            state.pause();
+        }
+        ctx.request_repaint_after(Duration::from_secs(1));
 
-        let mut res = Ok(());
+        // Nothing of the game can be shown once the player left it
+        if self.handle_keyboard_input(ctx, state)? {
+            return Ok(());
+        }
+
+        let mut res = Ok((Vec::new(), false));
         panel.show(ctx, |ui| {
             UnderlayUi::render(ui, state);
-            res = res
-                .and_then(|_| GridUi::render(ui, state))
-                .and_then(|_| HighlightUI::render(ui, state.clone()));
+            // The outlines are painted over the grid, so they can come after every move of the frame
+            res = GridUi::render(ui, state)
+                .and_then(|_| self.render_hint_buttons(ui, state))
+                .and_then(|_| self.render_history_buttons(ui, state))
+                .and_then(|_| state.get_win_status())
+                .inspect(|(broken, _)| HighlightUI::render(ui, state, broken));
+
+            ui.label(format!("Time {}", format_duration(state.get_elapsed())));
+
+            if let Some(seed) = state.get_game_seed() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Seed {} (n = {})", seed, state.get_n()));
+                    if ui.button("Copy").clicked() {
+                        ui.ctx().copy_text(seed.to_string());
+                    }
+                });
+            }
+
+            if let Some((asked, rated)) = state.get_missed_difficulty() {
+                ui.label(format!(
+                    "No {} layout was found, this one is {}",
+                    asked, rated
+                ));
+            }
+
+            ui.checkbox(&mut self.show_rule_list, "List broken rules");
         });
-        res?;
+        let (errors, game_won) = res?;
 
-        let (errors, game_won) = state.get_win_status()?;
+        if let Some(hint) = state.get_hint() {
+            Window::new("Hint")
+                .anchor(Align2::RIGHT_BOTTOM, Vec2::new(0.0, -15.0))
+                .show(ctx, |ui| {
+                    ui.label(format!("{}", hint));
+                });
+        }
 
-        // TODO only show these windows if debug
-        if !errors.is_empty() {
-            Window::new("Error list")
+        if self.show_rule_list && !errors.is_empty() {
+            Window::new("Broken rules")
                 .anchor(Align2::RIGHT_TOP, Vec2::new(0.0, 15.0))
                 .show(ctx, |ui| {
                     for error in errors {
@@ -37,114 +82,102 @@ impl InGameUi {
         }
 
         if game_won {
-            state.set_game_won();
+            state.set_game_won()?;
         }
 
         Ok(())
     }
 
-    fn handle_keyboard_input(&self, ctx: &Context, state: &mut State) -> QueensResult<()> {
-        let n = state.get_n();
+    fn render_hint_buttons(&self, ui: &mut Ui, state: &mut State) -> QueensResult<()> {
+        let mut res = Ok(());
+
+        ui.horizontal(|ui| {
+            if ui.button("Hint (H)").clicked() {
+                res = state.request_hint();
+            }
+            if ui.button("Show queen (Shift+H)").clicked() {
+                res = state.reveal_queen();
+            }
+        });
 
+        res
+    }
+
+    fn render_history_buttons(&self, ui: &mut Ui, state: &mut State) -> QueensResult<()> {
+        let mut res = Ok(());
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(state.can_undo(), Button::new("Undo (Ctrl+Z)"))
+                .clicked()
+            {
+                res = state.undo();
+            }
+            if ui
+                .add_enabled(state.can_redo(), Button::new("Redo (Ctrl+Y)"))
+                .clicked()
+            {
+                res = state.redo();
+            }
+            if ui.button("Clear").clicked() {
+                res = state.clear();
+            }
+        });
+
+        res
+    }
+
+    // Returns whether the player left the game
+    fn handle_keyboard_input(&self, ctx: &Context, state: &mut State) -> QueensResult<bool> {
         let cmd_ctrl_pressed = ctx.input(|x| x.modifiers.command_only());
-        let default_marked = 0;
-
-        if ctx.input(|x| x.key_pressed(Key::ArrowRight)) {
-            let new_coord = match state.get_marked() {
-                None => default_marked,
-                Some(c) => {
-                    if cmd_ctrl_pressed {
-                        if c % n == n - 1 {
-                            c
-                        } else {
-                            c + (n - (c % n)) - 1
-                        }
-                    } else {
-                        if c % n == n - 1 {
-                            c + 1 - n
-                        } else {
-                            c + 1
-                        }
-                    }
-                }
-            };
-            state.set_marked(Some(new_coord));
+
+        // Check redo first, as undo would also match with shift held
+        if ctx.input_mut(|x| {
+            x.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z)
+                || x.consume_key(Modifiers::COMMAND, Key::Y)
+        }) {
+            state.redo()?;
         }
-        if ctx.input(|x| x.key_pressed(Key::ArrowLeft)) {
-            let new_coord = match state.get_marked() {
-                None => default_marked,
-                Some(c) => {
-                    if cmd_ctrl_pressed {
-                        if c % n == 0 {
-                            c
-                        } else {
-                            c - (c % n)
-                        }
-                    } else {
-                        if c % n == 0 {
-                            c + n - 1
-                        } else {
-                            c - 1
-                        }
-                    }
-                }
-            };
-            state.set_marked(Some(new_coord));
+        if ctx.input_mut(|x| x.consume_key(Modifiers::COMMAND, Key::Z)) {
+            state.undo()?;
         }
-        if ctx.input(|x| x.key_pressed(Key::ArrowDown)) {
-            let new_coord = match state.get_marked() {
-                None => default_marked,
-                Some(c) => {
-                    if cmd_ctrl_pressed {
-                        if c >= n * (n - 1) {
-                            c
-                        } else {
-                            n * (n - 1) + (c % n)
-                        }
-                    } else {
-                        if c >= n * (n - 1) {
-                            c + n - n * n
-                        } else {
-                            c + n
-                        }
-                    }
-                }
-            };
-            state.set_marked(Some(new_coord));
-        }
-        if ctx.input(|x| x.key_pressed(Key::ArrowUp)) {
-            let new_coord = match state.get_marked() {
-                None => default_marked,
-                Some(c) => {
-                    if cmd_ctrl_pressed {
-                        if c < n {
-                            c
-                        } else {
-                            c % n
-                        }
-                    } else {
-                        if c < n {
-                            c + n * (n - 1)
-                        } else {
-                            c - n
-                        }
-                    }
-                }
-            };
-            state.set_marked(Some(new_coord));
+
+        let keys = [
+            (Key::ArrowUp, Direction::Up),
+            (Key::ArrowDown, Direction::Down),
+            (Key::ArrowLeft, Direction::Left),
+            (Key::ArrowRight, Direction::Right),
+        ];
+        for (key, direction) in keys {
+            if ctx.input(|x| x.key_pressed(key)) {
+                state.move_marker(direction, cmd_ctrl_pressed);
+            }
         }
         if ctx.input(|x| x.key_pressed(Key::Escape)) {
             if state.get_marked().is_some() {
                 state.set_marked(None);
             } else {
-                return Err(QueensError::RefreshRequested);
+                state.suspend();
+                return Ok(true);
             }
         }
-        if ctx.input(|x| x.key_pressed(Key::Space) || x.key_pressed(Key::Enter)) {
+        if ctx.input(|x| x.key_pressed(Key::Space)) {
             if let Some(c) = state.get_marked() {
-                state.flip_tile(c).expect("Could not flip tile");
+                state.flip_tile(c)?;
             }
         }
-        Ok(())
+        if ctx.input(|x| x.key_pressed(Key::Enter)) {
+            if let Some(c) = state.get_marked() {
+                state.toggle_queen(c)?;
+            }
+        }
+        if ctx.input(|x| x.key_pressed(Key::H)) {
+            if ctx.input(|x| x.modifiers.shift) {
+                state.reveal_queen()?;
+            } else {
+                state.request_hint()?;
+            }
+        }
+        Ok(false)
     }
 }
@@ -1,5 +1,5 @@
-use crate::model::state::State;
-use eframe::egui::{Color32, CornerRadius, Pos2, Rect, Stroke, StrokeKind, Ui, Vec2};
+use eframe::egui::{Color32, CornerRadius, Pos2, Rect, Stroke, StrokeKind, Ui};
+use queens::model::state::State;
 
 pub struct UnderlayUi {}
 
@@ -0,0 +1,100 @@
+use eframe::egui::{CentralPanel, CollapsingHeader, Color32, Context, Grid, Key, ScrollArea};
+use queens::errors::QueensResult;
+use queens::model::pack::{LevelId, Pack};
+use queens::model::state::State;
+
+#[derive(Default)]
+pub struct LevelsUi {}
+
+impl LevelsUi {
+    pub fn render(&self, ctx: &Context, state: &mut State) -> QueensResult<()> {
+        let (packs, log) = state.get_levels();
+        let completed = |id: &LevelId| log.as_ref().is_ok_and(|log| log.is_completed(id));
+        let mut chosen = None;
+        let mut back = false;
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Levels");
+            // Levels can still be played without the record of the solved ones
+            if let Err(e) = &log {
+                ui.colored_label(Color32::RED, e.to_string());
+            }
+
+            ScrollArea::vertical().show(ui, |ui| {
+                for pack in packs.iter() {
+                    let pack = match pack {
+                        Ok(pack) => pack,
+                        // One broken pack should not hide the others
+                        Err(e) => {
+                            ui.colored_label(Color32::RED, e.to_string());
+                            continue;
+                        }
+                    };
+
+                    let solved = pack
+                        .levels
+                        .iter()
+                        .filter(|level| completed(&Self::id(pack, &level.name)))
+                        .count();
+                    let title = match &pack.author {
+                        Some(author) => format!(
+                            "{} by {} ({}/{} solved)",
+                            pack.name,
+                            author,
+                            solved,
+                            pack.levels.len()
+                        ),
+                        None => format!("{} ({}/{} solved)", pack.name, solved, pack.levels.len()),
+                    };
+
+                    CollapsingHeader::new(title)
+                        .id_salt(&pack.name)
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            Grid::new(&pack.name)
+                                .striped(true)
+                                .num_columns(6)
+                                .show(ui, |ui| {
+                                    for level in pack.levels.iter() {
+                                        let id = Self::id(pack, &level.name);
+                                        let done = completed(&id);
+
+                                        ui.label(if done { "✔" } else { "" });
+                                        ui.label(&level.name);
+                                        let n = level.layout.get_n();
+                                        ui.label(format!("{} × {}", n, n));
+                                        ui.label(match level.difficulty {
+                                            Some(difficulty) => difficulty.to_string(),
+                                            None => String::new(),
+                                        });
+                                        ui.label(level.author.as_deref().unwrap_or(""));
+                                        if ui.button("Play").clicked() {
+                                            chosen = Some(id);
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                }
+            });
+
+            back = ui.button("Back").clicked();
+        });
+
+        if back || ctx.input(|x| x.key_pressed(Key::Escape)) {
+            state.show_main_menu();
+        }
+
+        match chosen {
+            Some(id) => state.load_level(id),
+            None => Ok(()),
+        }
+    }
+
+    fn id(pack: &Pack, level: &str) -> LevelId {
+        LevelId {
+            pack: pack.name.clone(),
+            level: level.to_string(),
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod levels_ui;
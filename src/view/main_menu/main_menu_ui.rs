@@ -1,47 +1,157 @@
-use crate::errors::QueensResult;
-use crate::model::layout::LayoutType;
-use crate::model::state::State;
-use eframe::egui::Event::Text;
-use eframe::egui::{CentralPanel, Context, Key, RadioButton, Slider};
+use crate::view::format_duration;
+use eframe::egui::{Button, CentralPanel, Color32, Context, Key, Slider, Ui};
+use queens::errors::QueensResult;
+use queens::model::daily::Daily;
+use queens::model::difficulty::Difficulty;
+use queens::model::layout::LayoutType;
+use queens::model::state::State;
 
 #[derive(Default)]
-pub struct MainMenuUi {}
+pub struct MainMenuUi {
+    // Seed as typed by the player
+    seed: String,
+}
 
 impl MainMenuUi {
-    pub fn render(&self, ctx: &Context, state: &mut State) -> QueensResult<()> {
+    pub fn render(&mut self, ctx: &Context, state: &mut State) -> QueensResult<()> {
+        let mut res = Ok(());
+
         CentralPanel::default().show(ctx, |ui| {
             ui.vertical(|ui| {
                 ui.label("This is the main menu");
                 let mut n = state.get_n();
-                ui.add(Slider::new(&mut n, 6..=12).text("Set n (can only be 10 right now)"));
+                let sizes = state.get_layout_type().get_sizes();
+                ui.add_enabled(
+                    sizes.start() != sizes.end(),
+                    Slider::new(&mut n, sizes).text("Set n"),
+                );
                 state.set_n(n);
 
                 ui.label("Select a layout type");
                 let mut layout_type = state.get_layout_type().clone();
                 ui.radio_value(&mut layout_type, LayoutType::Generated, "Generate a layout");
-                ui.radio_value(&mut layout_type, LayoutType::Easy, "Easily solvable");
                 ui.radio_value(
                     &mut layout_type,
-                    LayoutType::Complex,
-                    "Complex template (only for n = 10)",
+                    LayoutType::GeneratedUnique,
+                    "Generate a layout with a unique solution",
                 );
-                if layout_type == LayoutType::Complex {
-                    state.set_n(10);
-                }
+                ui.horizontal(|ui| {
+                    ui.label("Generate a layout of difficulty");
+                    for difficulty in Difficulty::ALL {
+                        ui.radio_value(
+                            &mut layout_type,
+                            LayoutType::Rated(difficulty),
+                            difficulty.to_string(),
+                        );
+                    }
+                });
+                ui.radio_value(&mut layout_type, LayoutType::Easy, "Easily solvable");
+                ui.radio_value(&mut layout_type, LayoutType::Complex, "Complex template");
                 state.set_layout_type(layout_type);
 
+                let mut auto_eliminate = state.get_auto_eliminate();
+                ui.checkbox(
+                    &mut auto_eliminate,
+                    "Cross out tiles ruled out by a placed queen",
+                );
+                state.set_auto_eliminate(auto_eliminate);
+
                 // todo set whether or not to move marker across sides
 
-                if ui.button("Play").clicked() {
-                    state.load_in_game();
+                ui.horizontal(|ui| {
+                    ui.label("Seed");
+                    ui.text_edit_singleline(&mut self.seed)
+                        .on_hover_text("Leave empty for a random layout");
+                });
+                let seed = self.parse_seed();
+                match seed {
+                    Some(seed) => state.set_seed(seed),
+                    None => {
+                        ui.colored_label(Color32::RED, "The seed must be a whole number");
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(seed.is_some(), Button::new("Play"))
+                        .clicked()
+                    {
+                        state.generate_in_game();
+                    }
+                    if ui
+                        .add_enabled(state.can_continue(), Button::new("Continue"))
+                        .clicked()
+                    {
+                        res = state.continue_game();
+                    }
+                    if ui
+                        .add_enabled(state.has_game(), Button::new("Save"))
+                        .clicked()
+                    {
+                        res = state.save();
+                    }
+                    if ui.button("Statistics").clicked() {
+                        state.show_statistics();
+                    }
+                    if ui.button("Levels").clicked() {
+                        state.show_levels();
+                    }
+                    if ui.button("Editor").clicked() {
+                        state.show_editor();
+                    }
+                });
+
+                ui.separator();
+                Self::render_daily(ui, state);
+                if ui.button("Play daily").clicked() {
+                    res = state.load_daily();
                 }
             });
         });
 
-        if ctx.input(|x| x.key_pressed(Key::Space) || x.key_pressed(Key::Enter)) {
-            state.load_in_game();
+        if self.parse_seed().is_some()
+            && ctx.input(|x| x.key_pressed(Key::Space) || x.key_pressed(Key::Enter))
+        {
+            state.generate_in_game();
+        }
+
+        res
+    }
+
+    // Today's puzzle and how the player has done on the daily puzzles
+    fn render_daily(ui: &mut Ui, state: &mut State) {
+        let daily = Daily::today();
+        ui.label(format!("Daily puzzle of {} (n = {})", daily.date, daily.n));
+
+        // A broken results file should not keep the menu from working
+        let log = match state.get_daily_log() {
+            Ok(log) => log,
+            Err(e) => {
+                ui.colored_label(Color32::RED, e.to_string());
+                return;
+            }
+        };
+
+        if let Some(completion) = log.get(daily.date) {
+            ui.label(format!(
+                "Solved today in {}",
+                format_duration(completion.time)
+            ));
         }
 
-        Ok(())
+        let streak = log.streak(daily.date);
+        ui.label(format!(
+            "Streak: {} day{}",
+            streak,
+            if streak == 1 { "" } else { "s" }
+        ));
+    }
+
+    // The typed seed, none if it is not a number
+    fn parse_seed(&self) -> Option<Option<u64>> {
+        match self.seed.trim() {
+            "" => Some(None),
+            seed => seed.parse().ok().map(Some),
+        }
     }
 }
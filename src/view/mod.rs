@@ -1,2 +1,20 @@
+use std::time::Duration;
+
+pub mod editor;
+pub mod generating;
 pub mod in_game;
+pub mod levels;
 pub mod main_menu;
+pub mod statistics;
+pub mod tile_widget;
+
+// Time as minutes and seconds, with hours once they are needed
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
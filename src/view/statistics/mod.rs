@@ -0,0 +1 @@
+pub mod statistics_ui;
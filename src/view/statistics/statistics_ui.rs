@@ -0,0 +1,85 @@
+use crate::view::format_duration;
+use chrono::Local;
+use eframe::egui::{CentralPanel, Color32, Context, Grid, Key, ScrollArea, Ui};
+use queens::errors::QueensResult;
+use queens::model::state::State;
+use queens::model::stats::GroupStats;
+
+#[derive(Default)]
+pub struct StatisticsUi {}
+
+impl StatisticsUi {
+    pub fn render(&self, ctx: &Context, state: &mut State) -> QueensResult<()> {
+        let groups = state
+            .get_statistics()
+            .map(|statistics| statistics.groups(Local::now().date_naive()));
+
+        CentralPanel::default().show(ctx, |ui| {
+            ui.heading("Statistics");
+
+            // A broken statistics file should not throw away the game left to continue
+            match groups {
+                Ok(groups) => Self::render_groups(ui, groups),
+                Err(e) => {
+                    ui.colored_label(Color32::RED, e.to_string());
+                }
+            }
+
+            if ui.button("Back").clicked() {
+                state.show_main_menu();
+            }
+        });
+
+        if ctx.input(|x| x.key_pressed(Key::Escape)) {
+            state.show_main_menu();
+        }
+
+        Ok(())
+    }
+
+    fn render_groups(ui: &mut Ui, groups: Vec<GroupStats>) {
+        if groups.is_empty() {
+            ui.label("No games won yet");
+        }
+
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("statistics")
+                .striped(true)
+                .num_columns(8)
+                .show(ui, |ui| {
+                    let headers = [
+                        "n",
+                        "Layout",
+                        "Difficulty",
+                        "Won",
+                        "Best",
+                        "Average",
+                        "Fewest hints",
+                        "Streak",
+                    ];
+                    for header in headers {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+
+                    for group in groups {
+                        ui.label(group.n.to_string());
+                        ui.label(match &group.layout_type {
+                            Some(layout_type) => layout_type.to_string(),
+                            None => "Custom".to_string(),
+                        });
+                        ui.label(match group.difficulty {
+                            Some(difficulty) => difficulty.to_string(),
+                            None => "-".to_string(),
+                        });
+                        ui.label(group.played.to_string());
+                        ui.label(format_duration(group.best));
+                        ui.label(format_duration(group.average));
+                        ui.label(group.fewest_hints.to_string());
+                        ui.label(format!("{} days", group.streak));
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
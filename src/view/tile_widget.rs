@@ -0,0 +1,43 @@
+// Drawing tiles with egui
+use eframe::egui::{vec2, Button, Color32, Response, RichText, Ui, Vec2, Widget};
+use queens::distinct_colors::get_distinct_rgb;
+use queens::model::tile::{Tile, TileState};
+
+pub static TILE_SIZE: Vec2 = vec2(32.0, 32.0);
+
+pub fn get_distinct_color(c: u8) -> Color32 {
+    let [r, g, b] = get_distinct_rgb(c);
+    Color32::from_rgb(r, g, b)
+}
+
+pub fn get_distinct_checked_color(c: u8) -> Color32 {
+    get_distinct_color(c).gamma_multiply_u8(191)
+}
+
+// Tile as a button colored by its area
+pub struct TileWidget(pub Tile);
+
+impl TileWidget {
+    pub fn get_color(&self) -> Color32 {
+        if self.0.is_queen() {
+            get_distinct_checked_color(self.0.get_raw_color())
+        } else {
+            get_distinct_color(self.0.get_raw_color())
+        }
+    }
+}
+
+impl Widget for TileWidget {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let button = Button::new(match self.0.get_state() {
+            TileState::Empty => RichText::from("").color(Color32::BLACK),
+            TileState::Crossed => RichText::new("×").color(Color32::BLACK).small(),
+            TileState::AutoCrossed => RichText::new("×").color(Color32::DARK_GRAY).small(),
+            TileState::Queen => RichText::new("♛").color(Color32::BLACK).size(20.0),
+        })
+        .min_size(TILE_SIZE)
+        .fill(self.get_color());
+
+        button.ui(ui)
+    }
+}